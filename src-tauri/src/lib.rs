@@ -26,12 +26,21 @@ mod modular_agent_desktop;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    modular_agent_desktop::layout::init();
+    let log_dir_target = match modular_agent_desktop::layout::log_dir() {
+        Some(path) => TargetKind::Folder {
+            path,
+            file_name: None,
+        },
+        None => TargetKind::LogDir { file_name: None },
+    };
+
     tauri::Builder::default()
         .plugin(
             tauri_plugin_log::Builder::new()
                 .targets([
                     Target::new(TargetKind::Stdout),
-                    Target::new(TargetKind::LogDir { file_name: None }),
+                    Target::new(log_dir_target),
                     Target::new(TargetKind::Webview),
                 ])
                 .level(log::LevelFilter::Info)
@@ -76,6 +85,9 @@ pub fn run() {
 
             let app_handle = app.handle().clone();
             tauri::async_runtime::block_on(async move {
+                modular_agent_desktop::layout::migrate(&app_handle).unwrap_or_else(|e| {
+                    log::error!("Failed to migrate data: {}", e);
+                });
                 modular_agent_desktop::settings::init(&app_handle).unwrap_or_else(|e| {
                     panic!("Failed to initialize settings: {}", e);
                 });
@@ -87,6 +99,14 @@ pub fn run() {
                     log::error!("Failed to initialize agent: {}", e);
                     app_handle.exit(1);
                 });
+                modular_agent_desktop::search::init(&app_handle);
+                modular_agent_desktop::tree::init(&app_handle);
+                modular_agent_desktop::workspace::init(&app_handle).unwrap_or_else(|e| {
+                    log::error!("Failed to initialize workspaces: {}", e);
+                });
+                modular_agent_desktop::watcher::init(&app_handle).unwrap_or_else(|e| {
+                    log::error!("Failed to initialize presets watcher: {}", e);
+                });
                 modular_agent_desktop::settings::load_agent_global_configs(&app_handle)
                     .unwrap_or_else(|e| {
                         log::error!("Failed to load agent global configs: {}", e);
@@ -112,17 +132,51 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             exit_app_cmd,
+            modular_agent_desktop::layout::get_data_layout_cmd,
             modular_agent_desktop::app::new_preset_with_name_cmd,
+            modular_agent_desktop::templates::list_templates_cmd,
+            modular_agent_desktop::templates::new_preset_from_template_cmd,
             modular_agent_desktop::app::move_preset_cmd,
             modular_agent_desktop::app::move_folder_cmd,
+            modular_agent_desktop::app::rename_preset_cmd,
+            modular_agent_desktop::app::rename_folder_cmd,
+            modular_agent_desktop::app::create_folder_cmd,
+            modular_agent_desktop::app::delete_folder_cmd,
             modular_agent_desktop::app::delete_preset_cmd,
             modular_agent_desktop::app::close_preset_cmd,
             modular_agent_desktop::app::import_preset_cmd,
+            modular_agent_desktop::app::import_preset_from_text_cmd,
             modular_agent_desktop::app::save_preset_cmd,
+            modular_agent_desktop::app::get_preset_revision_cmd,
+            modular_agent_desktop::metadata::set_preset_locked_cmd,
+            modular_agent_desktop::metadata::get_preset_metadata_cmd,
+            modular_agent_desktop::metadata::set_preset_metadata_cmd,
+            modular_agent_desktop::metadata::find_presets_by_tags_cmd,
+            modular_agent_desktop::metadata::list_preset_tags_cmd,
+            modular_agent_desktop::metadata::set_folder_read_only_cmd,
+            modular_agent_desktop::app::duplicate_preset_cmd,
+            modular_agent_desktop::autosave::get_dirty_presets_cmd,
+            modular_agent_desktop::bundle::export_preset_cmd,
+            modular_agent_desktop::bundle::export_folder_cmd,
+            modular_agent_desktop::bundle::import_presets_cmd,
             modular_agent_desktop::app::start_preset_cmd,
             modular_agent_desktop::app::stop_preset_cmd,
+            modular_agent_desktop::bulk::start_presets_cmd,
+            modular_agent_desktop::bulk::stop_presets_cmd,
             modular_agent_desktop::app::get_dir_entries_cmd,
+            modular_agent_desktop::app::get_dir_entry_infos_cmd,
+            modular_agent_desktop::tree::get_preset_tree_cmd,
             modular_agent_desktop::app::open_preset_cmd,
+            modular_agent_desktop::trash::list_trash_cmd,
+            modular_agent_desktop::trash::restore_trash_entry_cmd,
+            modular_agent_desktop::trash::purge_trash_cmd,
+            modular_agent_desktop::versions::list_preset_versions_cmd,
+            modular_agent_desktop::versions::get_preset_version_cmd,
+            modular_agent_desktop::versions::restore_preset_version_cmd,
+            modular_agent_desktop::diff::diff_presets_cmd,
+            modular_agent_desktop::search::search_presets_cmd,
+            modular_agent_desktop::workspace::list_workspaces_cmd,
+            modular_agent_desktop::validate::validate_preset_cmd,
             modular_agent_desktop::settings::get_core_settings_cmd,
             modular_agent_desktop::settings::set_core_settings_cmd,
             modular_agent_desktop::settings::set_global_configs_cmd,
@@ -176,6 +230,7 @@ pub fn run() {
                         .unwrap_or_else(|e| {
                             log::error!("Failed to save window state: {}", e);
                         });
+                    modular_agent_desktop::autosave::flush(app).await;
                     modular_agent_desktop::app::quit(app);
                     modular_agent_desktop::settings::quit(app);
                 });
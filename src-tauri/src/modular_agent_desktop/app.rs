@@ -1,10 +1,12 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
 use std::{path::PathBuf, sync::Mutex};
 
 use anyhow::{anyhow, bail, Context as _, Result};
 use dirs;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Manager, State};
 
 use modular_agent_core::mcp::register_tools_from_mcp_json;
@@ -13,18 +15,38 @@ use modular_agent_core::{ModularAgent, PresetSpec};
 use tauri_plugin_modular_agent::ModularAgentExt;
 
 use crate::modular_agent_desktop::{
-    observer::start_modular_agent_observer, settings::CoreSettings,
+    autosave, layout, metadata,
+    observer::start_modular_agent_observer,
+    schema::{self, MigrationReport},
+    search,
+    settings::CoreSettings,
+    trash, tree, validate, versions, watcher,
+    workspace::{self, split_workspace, workspace_prefix, workspace_root},
 };
 
-static MODULAR_AGENT_PATH: &'static str = ".modular_agent";
 static MODULAR_AGENT_PRESETS_PATH: &'static str = "presets";
 
-const EMIT_PRESET_LIST_CHANGED: &str = "ma:preset_list_changed";
+/// Environment variable that overrides the data directory.
+const MODULAR_AGENT_HOME_ENV: &str = "MODULAR_AGENT_HOME";
+
+/// Command-line flag that overrides the data directory, e.g. `--data-dir /path/to/dir`.
+const DATA_DIR_ARG: &str = "--data-dir";
+
+/// Data directory resolved in `init`.
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Marker file that keeps an intentionally empty folder from being cleaned up.
+const FOLDER_KEEP_FILE: &str = ".keep";
+
+pub(crate) const EMIT_PRESET_LIST_CHANGED: &str = "ma:preset_list_changed";
 const EMIT_PRESET_RENAMED: &str = "ma:preset_renamed";
+const EMIT_PRESET_RELOADED: &str = "ma:preset_reloaded";
+const EMIT_PRESET_REPAIRED: &str = "ma:preset_repaired";
+const EMIT_PRESET_MIGRATED: &str = "ma:preset_migrated";
 
 #[derive(Clone, Serialize)]
-struct PresetListChangedPayload {
-    path: String,
+pub(crate) struct PresetListChangedPayload {
+    pub(crate) path: String,
 }
 
 #[derive(Clone, Serialize)]
@@ -34,20 +56,177 @@ struct PresetRenamedPayload {
     new_name: String,
 }
 
+/// An open preset was reloaded from its file under a new ID.
+#[derive(Clone, Serialize)]
+struct PresetReloadedPayload {
+    id: String,
+    #[serde(rename = "newId")]
+    new_id: String,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirEntryKind {
+    Folder,
+    Preset,
+}
+
+/// A directory listing entry with preset metadata.
+#[derive(Clone, Serialize)]
+pub struct DirEntryInfo {
+    kind: DirEntryKind,
+
+    /// Base name of the folder or preset.
+    name: String,
+
+    /// Full folder path or preset name.
+    path: String,
+
+    /// Milliseconds since the Unix epoch.
+    modified: Option<u64>,
+
+    /// File size in bytes (presets only).
+    size: Option<u64>,
+
+    /// Number of agents (presets only, if the file can be parsed).
+    agent_count: Option<usize>,
+
+    open: bool,
+    running: bool,
+    auto_start: bool,
+
+    /// Locked preset, or folder marked read-only.
+    locked: bool,
+
+    /// Cannot be changed, because it is locked or inside a read-only folder.
+    read_only: bool,
+
+    /// Description, tags and looks (presets only, if set).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<metadata::PresetMetadata>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DuplicatedPreset {
+    name: String,
+
+    /// ID of the copy if it was opened.
+    id: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct PresetRepairedPayload {
+    id: String,
+    name: String,
+    error: String,
+}
+
+#[derive(Clone, Serialize)]
+struct PresetMigratedPayload {
+    id: String,
+    name: String,
+    report: MigrationReport,
+}
+
+/// State of a preset file as last loaded by a caller, to detect edits made elsewhere.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PresetRevision {
+    /// Hash of the file content.
+    hash: String,
+
+    /// Modification time in milliseconds since the Unix epoch.
+    mtime: u64,
+}
+
+/// Revision the caller expects on disk when saving. Either field may be omitted;
+/// omitting both overwrites whatever is on disk.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ExpectedRevision {
+    hash: Option<String>,
+    mtime: Option<u64>,
+}
+
+/// The preset file changed since the caller loaded it.
+#[derive(Clone, Debug, Serialize)]
+pub struct PresetConflict {
+    /// Always "conflict", so callers can tell it from plain error messages.
+    kind: &'static str,
+    message: String,
+
+    /// Current revision on disk, or `None` if the file was deleted.
+    revision: Option<PresetRevision>,
+
+    /// Current spec on disk, or `None` if the file was deleted or cannot be parsed.
+    disk_spec: Option<PresetSpec>,
+}
+
+impl std::fmt::Display for PresetConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for PresetConflict {}
+
+/// Error of `save_preset_cmd`. Serialized as a plain message, or as a conflict object.
+#[derive(Clone, Serialize)]
+#[serde(untagged)]
+pub enum SavePresetError {
+    Conflict(Box<PresetConflict>),
+    Failed(String),
+}
+
+/// Join a directory path and a base name into a preset name.
+/// e.g., ("Category", "MyPreset") -> "Category/MyPreset", ("", "MyPreset") -> "MyPreset",
+/// ("team:", "MyPreset") -> "team:MyPreset"
+pub(crate) fn join_preset_path(dir: &str, basename: &str) -> String {
+    if dir.is_empty() || dir.ends_with(workspace::WORKSPACE_SEPARATOR) {
+        format!("{}{}", dir, basename)
+    } else {
+        format!("{}/{}", dir, basename)
+    }
+}
+
 /// Extract parent directory path from a preset name.
-/// e.g., "Category/MyPreset" -> "Category", "MyPreset" -> ""
-fn parent_preset_path(name: &str) -> String {
-    match name.rfind('/') {
-        Some(i) => name[..i].to_string(),
-        None => String::new(),
+/// e.g., "Category/MyPreset" -> "Category", "MyPreset" -> "", "team:MyPreset" -> "team:"
+pub(crate) fn parent_preset_path(name: &str) -> String {
+    let (workspace, path) = split_workspace(name);
+    match path.rfind('/') {
+        Some(i) => format!("{}{}", workspace_prefix(workspace), &path[..i]),
+        None => workspace_prefix(workspace),
     }
 }
 
+/// Extract the base name from a preset name or folder path.
+/// e.g., "Category/MyPreset" -> "MyPreset", "team:MyPreset" -> "MyPreset"
+pub(crate) fn preset_basename(name: &str) -> &str {
+    split_workspace(name).1.rsplit('/').next().unwrap_or(name)
+}
+
+/// Whether `basename` is a single name, without folders or a workspace.
+pub(crate) fn is_valid_basename(basename: &str) -> bool {
+    !basename.contains('/') && !basename.contains(workspace::WORKSPACE_SEPARATOR)
+}
+
 pub struct ModularAgentApp {
     ma: ModularAgent,
 
     /// Map of preset name to preset ID.
     presets: Arc<Mutex<HashMap<String, String>>>,
+
+    /// Map of preset ID to the spec last opened or saved, for dirty tracking.
+    saved_specs: Arc<Mutex<HashMap<String, serde_json::Value>>>,
+
+    /// Map of preset ID to the revision of its file when last opened or saved,
+    /// to detect edits made elsewhere.
+    revisions: Arc<Mutex<HashMap<String, PresetRevision>>>,
+
+    /// Map of preset ID to name, of the open presets last reported as dirty.
+    reported_dirty: Arc<Mutex<HashMap<String, String>>>,
+
+    /// IDs of the open presets whose autosave was skipped because their file changed
+    /// elsewhere, reported once until they are saved.
+    autosave_conflicts: Arc<Mutex<HashSet<String>>>,
 }
 
 impl ModularAgentApp {
@@ -55,24 +234,29 @@ impl ModularAgentApp {
         Self {
             ma: ma.clone(),
             presets: Arc::new(Mutex::new(HashMap::new())),
+            saved_specs: Arc::new(Mutex::new(HashMap::new())),
+            revisions: Arc::new(Mutex::new(HashMap::new())),
+            reported_dirty: Arc::new(Mutex::new(HashMap::new())),
+            autosave_conflicts: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
     // Preset
 
     /// Create a new preset.
-    pub fn new_preset_with_name(&self, name: String) -> Result<String> {
-        if !is_valid_preset_name(&name) {
+    pub fn new_preset_with_name(&self, app: &AppHandle, name: String) -> Result<String> {
+        if !is_valid_preset_name(app, &name) {
             return Err(anyhow!("Invalid preset name: {}", name));
         }
+        metadata::ensure_folder_writable(app, &parent_preset_path(&name))?;
         let id = self.ma.new_preset_with_name(name.clone())?;
         let mut presets = self.presets.lock().unwrap();
         presets.insert(name, id.clone());
         Ok(id)
     }
 
-    pub async fn open_preset(&self, name: String) -> Result<String> {
-        if !is_valid_preset_name(&name) {
+    pub async fn open_preset(&self, app: &AppHandle, name: String) -> Result<String> {
+        if !is_valid_preset_name(app, &name) {
             return Err(anyhow!("Invalid preset name: {}", name));
         }
 
@@ -81,8 +265,19 @@ impl ModularAgentApp {
             return Ok(id);
         }
 
+        // Upgrade files saved in an older format, keeping a copy of the original.
+        // Locked presets and read-only folders are never written on open.
+        let path = preset_path(app, &name)?;
+        let migrated = if metadata::is_preset_read_only(app, &name) {
+            None
+        } else {
+            schema::migrate_preset_file(&path, |path, json| write_preset_file(app, path, json))?
+        };
+
+        // Restore the preset file from its backup if it is broken
+        let repaired = repair_preset_file(app, &path)?;
+
         // open the preset file
-        let path = preset_path(&name)?;
         let id = self
             .ma
             .open_preset_from_file(path.to_string_lossy().as_ref(), Some(name.clone()))
@@ -91,14 +286,34 @@ impl ModularAgentApp {
         // Store into the presets map
         {
             let mut presets = self.presets.lock().unwrap();
-            presets.insert(name, id.clone());
+            presets.insert(name.clone(), id.clone());
+        }
+        if let Some(spec) = self.ma.get_preset_spec(&id).await {
+            self.set_saved_spec(&id, &spec);
+        }
+        self.record_revision(app, &id, &name);
+
+        // Report the recovered error so the user knows recent changes may be lost
+        if let Some(error) = repaired {
+            log::warn!("Preset {} was restored from its backup: {}", name, error);
+            let _ = app.emit(EMIT_PRESET_REPAIRED, PresetRepairedPayload {
+                id: id.clone(),
+                name: name.clone(),
+                error,
+            });
+        }
+        if let Some(report) = migrated {
+            log::info!("Preset {} was migrated: {:?}", name, report);
+            emit_preset_migrated(app, &id, &name, report);
         }
 
         Ok(id)
     }
 
-    /// Delete a preset by the given name, and delete its file.
-    pub async fn delete_preset(&self, name: &str) -> Result<()> {
+    /// Delete a preset by the given name, and move its file to the trash.
+    pub async fn delete_preset(&self, app: &AppHandle, name: &str) -> Result<()> {
+        metadata::ensure_preset_writable(app, name)?;
+
         // If the preset is opened, remove it from ModularAgent core.
         if let Some(preset_id) = self.get_preset_id(name) {
             self.ma.remove_preset(&preset_id).await?;
@@ -110,11 +325,14 @@ impl ModularAgentApp {
             presets.remove(name);
         }
 
-        // Delete the file from disk
-        let preset_path = preset_path(name)?;
+        // Move the file to the trash
+        let preset_path = preset_path(app, name)?;
         if preset_path.exists() {
-            std::fs::remove_file(preset_path).with_context(|| "Failed to remove preset file")?;
+            watcher::mark_self_write(app, &preset_path);
+            trash::trash_preset(name, &preset_path)?;
         }
+        search::remove_preset(app, name);
+        tree::invalidate(app);
 
         Ok(())
     }
@@ -125,31 +343,47 @@ impl ModularAgentApp {
             bail!("Invalid preset name");
         }
 
-        let basename = name.rsplit('/').next().unwrap_or(name);
-        let new_name = if target_dir.is_empty() {
-            basename.to_string()
-        } else {
-            format!("{}/{}", target_dir, basename)
-        };
+        let new_name = join_preset_path(target_dir, preset_basename(name));
+
+        self.relocate_preset(app, name, &new_name).await
+    }
+
+    /// Rename a preset in place, keeping its parent directory.
+    pub async fn rename_preset(&self, app: &AppHandle, name: &str, new_basename: &str) -> Result<()> {
+        if name.contains("..") || name.contains('\\') {
+            bail!("Invalid preset name");
+        }
+        if !is_valid_basename(new_basename) {
+            bail!("Invalid preset name: {}", new_basename);
+        }
+
+        let new_name = join_preset_path(&parent_preset_path(name), new_basename);
 
+        self.relocate_preset(app, name, &new_name).await
+    }
+
+    /// Move a preset file to `new_name`, updating open presets and settings.
+    async fn relocate_preset(&self, app: &AppHandle, name: &str, new_name: &str) -> Result<()> {
         if name == new_name {
             return Ok(());
         }
 
-        if !is_valid_preset_name(&new_name) {
+        if !is_valid_preset_name(app, new_name) {
             bail!("Invalid preset name: {}", new_name);
         }
+        metadata::ensure_preset_writable(app, name)?;
+        metadata::ensure_folder_writable(app, &parent_preset_path(new_name))?;
 
         // Block moving running presets
         if let Some(id) = self.get_preset_id(name) {
             let infos = self.ma.get_preset_infos().await;
             if infos.iter().any(|p| p.id == id && p.running) {
-                bail!("Cannot move a running preset. Stop it first.");
+                bail!("Cannot move or rename a running preset. Stop it first.");
             }
         }
 
-        let old_path = preset_path(name)?;
-        let new_path = preset_path(&new_name)?;
+        let old_path = preset_path(app, name)?;
+        let new_path = preset_path(app, new_name)?;
 
         if !old_path.exists() {
             bail!("Preset file not found: {}", name);
@@ -165,41 +399,49 @@ impl ModularAgentApp {
             }
         }
 
-        // Move file — source and target are always under ~/.modular_agent/presets/
-        std::fs::rename(&old_path, &new_path)
+        // Move file — source and target are always under a workspace root
+        watcher::mark_self_write(app, &old_path);
+        watcher::mark_self_write(app, &new_path);
+        move_path(&old_path, &new_path)
             .with_context(|| format!("Failed to move preset: {} -> {}", name, new_name))?;
+        move_preset_sidecars(&old_path, &new_path);
 
         // Update in-memory state if preset is open
         if let Some(id) = self.get_preset_id(name) {
             {
                 let mut presets = self.presets.lock().unwrap();
                 presets.remove(name);
-                presets.insert(new_name.clone(), id.clone());
+                presets.insert(new_name.to_string(), id.clone());
             }
             // Update core Preset.name
-            if let Err(e) = self.ma.rename_preset(&id, new_name.clone()).await {
-                log::warn!("move_preset: rename_preset({}) failed: {}", id, e);
+            if let Err(e) = self.ma.rename_preset(&id, new_name.to_string()).await {
+                log::warn!("relocate_preset: rename_preset({}) failed: {}", id, e);
             }
             let _ = app.emit(EMIT_PRESET_RENAMED, PresetRenamedPayload {
                 id,
-                new_name: new_name.clone(),
+                new_name: new_name.to_string(),
             });
         }
 
+        // Move saved snapshots and the search index entry along with the preset
+        versions::move_versions(name, new_name);
+        search::rename_preset(app, name, new_name);
+        tree::invalidate(app);
+
         // Update auto_start_presets
-        update_auto_start_presets(app, name, &new_name);
+        update_auto_start_presets(app, name, new_name);
 
         // Emit list changed for both old and new parent directories
         let old_parent = parent_preset_path(name);
-        let new_parent = parent_preset_path(&new_name);
+        let new_parent = parent_preset_path(new_name);
         let _ = app.emit(EMIT_PRESET_LIST_CHANGED, PresetListChangedPayload { path: old_parent.clone() });
-        let _ = app.emit(EMIT_PRESET_LIST_CHANGED, PresetListChangedPayload { path: new_parent });
+        if new_parent != old_parent {
+            let _ = app.emit(EMIT_PRESET_LIST_CHANGED, PresetListChangedPayload { path: new_parent });
+        }
 
         // Clean up empty ancestor directories
         if let Some(parent) = old_path.parent() {
-            if let Ok(root) = presets_dir() {
-                cleanup_empty_ancestors(app, parent, &root);
-            }
+            cleanup_empty_ancestors(app, parent, split_workspace(name).0);
         }
 
         Ok(())
@@ -208,19 +450,14 @@ impl ModularAgentApp {
     /// Move a folder (and all its contents) to a different directory.
     pub async fn move_folder(&self, app: &AppHandle, path: &str, target_dir: &str) -> Result<()> {
         // Validate paths to prevent path traversal
-        if !path.is_empty() && (path.contains("..") || path.contains('\\') || path.starts_with('/')) {
+        if !path.is_empty() && (path.contains("..") || path.contains('\\') || split_workspace(path).1.starts_with('/')) {
             bail!("Invalid folder path");
         }
-        if !target_dir.is_empty() && (target_dir.contains("..") || target_dir.contains('\\') || target_dir.starts_with('/')) {
+        if !target_dir.is_empty() && (target_dir.contains("..") || target_dir.contains('\\') || split_workspace(target_dir).1.starts_with('/')) {
             bail!("Invalid target directory");
         }
 
-        let basename = path.rsplit('/').next().unwrap_or(path);
-        let new_path_str = if target_dir.is_empty() {
-            basename.to_string()
-        } else {
-            format!("{}/{}", target_dir, basename)
-        };
+        let new_path_str = join_preset_path(target_dir, preset_basename(path));
 
         if path == new_path_str {
             return Ok(());
@@ -232,9 +469,34 @@ impl ModularAgentApp {
             bail!("Cannot move a folder into itself");
         }
 
-        let presets_root = presets_dir()?;
-        let old_dir = presets_root.join(path);
-        let new_dir = presets_root.join(&new_path_str);
+        self.relocate_folder(app, path, &new_path_str).await
+    }
+
+    /// Rename a folder in place, keeping its parent directory.
+    pub async fn rename_folder(&self, app: &AppHandle, path: &str, new_basename: &str) -> Result<()> {
+        if !is_valid_preset_name(app, path) {
+            bail!("Invalid folder path");
+        }
+        if !is_valid_basename(new_basename) {
+            bail!("Invalid folder name: {}", new_basename);
+        }
+
+        let new_path_str = join_preset_path(&parent_preset_path(path), new_basename);
+
+        if path == new_path_str {
+            return Ok(());
+        }
+        if !is_valid_preset_name(app, &new_path_str) {
+            bail!("Invalid folder name: {}", new_basename);
+        }
+
+        self.relocate_folder(app, path, &new_path_str).await
+    }
+
+    /// Move a folder to `new_path_str`, updating open presets and settings.
+    async fn relocate_folder(&self, app: &AppHandle, path: &str, new_path_str: &str) -> Result<()> {
+        let old_dir = folder_path(app, path)?;
+        let new_dir = folder_path(app, new_path_str)?;
 
         if !old_dir.exists() || !old_dir.is_dir() {
             bail!("Folder not found: {}", path);
@@ -242,8 +504,11 @@ impl ModularAgentApp {
         if new_dir.exists() {
             bail!("A folder with this name already exists: {}", new_path_str);
         }
+        metadata::ensure_folder_contents_writable(app, path)?;
+        metadata::ensure_folder_writable(app, &parent_preset_path(new_path_str))?;
 
         // Block if any preset inside the folder is running
+        let self_prefix = format!("{}/", path);
         let infos_needed: Vec<String> = {
             let presets = self.presets.lock().unwrap();
             presets
@@ -256,7 +521,7 @@ impl ModularAgentApp {
             let infos = self.ma.get_preset_infos().await;
             for id in &infos_needed {
                 if infos.iter().any(|p| &p.id == id && p.running) {
-                    bail!("Cannot move folder: a preset inside it is running. Stop it first.");
+                    bail!("Cannot move or rename folder: a preset inside it is running. Stop it first.");
                 }
             }
         }
@@ -269,7 +534,7 @@ impl ModularAgentApp {
         }
 
         // Move directory
-        std::fs::rename(&old_dir, &new_dir)
+        move_path(&old_dir, &new_dir)
             .with_context(|| format!("Failed to move folder: {} -> {}", path, new_path_str))?;
 
         // Update all open presets that were inside the moved folder
@@ -292,7 +557,7 @@ impl ModularAgentApp {
                 presets.insert(new_name.clone(), id.clone());
             }
             if let Err(e) = self.ma.rename_preset(id, new_name.clone()).await {
-                log::warn!("move_folder: rename_preset({}) failed: {}", id, e);
+                log::warn!("relocate_folder: rename_preset({}) failed: {}", id, e);
             }
             let _ = app.emit(EMIT_PRESET_RENAMED, PresetRenamedPayload {
                 id: id.clone(),
@@ -300,25 +565,140 @@ impl ModularAgentApp {
             });
         }
 
+        // Move saved snapshots and search index entries along with the folder
+        versions::move_versions(path, new_path_str);
+        search::rename_prefix(app, &old_prefix, &new_prefix);
+        tree::invalidate(app);
+
         // Update auto_start_presets for all affected entries
         update_auto_start_presets_prefix(app, &old_prefix, &new_prefix);
 
         // Emit list changed for both old and new parent directories
         let old_parent = parent_preset_path(path);
-        let new_parent = parent_preset_path(&new_path_str);
+        let new_parent = parent_preset_path(new_path_str);
         let _ = app.emit(EMIT_PRESET_LIST_CHANGED, PresetListChangedPayload { path: old_parent.clone() });
-        let _ = app.emit(EMIT_PRESET_LIST_CHANGED, PresetListChangedPayload { path: new_parent });
+        if new_parent != old_parent {
+            let _ = app.emit(EMIT_PRESET_LIST_CHANGED, PresetListChangedPayload { path: new_parent });
+        }
 
         // Clean up empty ancestor directories
         if let Some(parent) = old_dir.parent() {
-            cleanup_empty_ancestors(app, parent, &presets_root);
+            cleanup_empty_ancestors(app, parent, split_workspace(path).0);
         }
 
         Ok(())
     }
 
-    pub fn save_preset(&self, name: String, spec: PresetSpec) -> Result<()> {
-        let preset_path = preset_path(&name)?;
+    /// Delete a folder and everything in it, moving it to the trash.
+    /// Open presets inside the folder are closed; running ones block the deletion.
+    pub async fn delete_folder(&self, app: &AppHandle, path: &str) -> Result<()> {
+        if path.is_empty() || !is_valid_preset_name(app, path) {
+            bail!("Invalid folder path");
+        }
+
+        let dir = folder_path(app, path)?;
+        if !dir.is_dir() {
+            bail!("Folder not found: {}", path);
+        }
+        metadata::ensure_folder_contents_writable(app, path)?;
+
+        // Block if any preset inside the folder is running
+        let prefix = format!("{}/", path);
+        let affected: Vec<(String, String)> = {
+            let presets = self.presets.lock().unwrap();
+            presets
+                .iter()
+                .filter(|(name, _)| name.starts_with(&prefix))
+                .map(|(name, id)| (name.clone(), id.clone()))
+                .collect()
+        };
+        if !affected.is_empty() {
+            let infos = self.ma.get_preset_infos().await;
+            for (_, id) in &affected {
+                if infos.iter().any(|p| &p.id == id && p.running) {
+                    bail!("Cannot delete folder: a preset inside it is running. Stop it first.");
+                }
+            }
+        }
+
+        // Close open presets inside the folder
+        for (name, id) in &affected {
+            if let Err(e) = self.ma.remove_preset(id).await {
+                log::warn!("delete_folder: remove_preset({}) failed: {}", id, e);
+            }
+            let mut presets = self.presets.lock().unwrap();
+            presets.remove(name);
+        }
+
+        trash::trash_folder(path, &dir)?;
+        search::remove_prefix(app, &prefix);
+        tree::invalidate(app);
+
+        // Remove auto_start_presets entries inside the folder
+        remove_auto_start_presets_prefix(app, &prefix);
+
+        let _ = app.emit(
+            EMIT_PRESET_LIST_CHANGED,
+            PresetListChangedPayload {
+                path: parent_preset_path(path),
+            },
+        );
+
+        // Clean up empty ancestor directories
+        if let Some(parent) = dir.parent() {
+            cleanup_empty_ancestors(app, parent, split_workspace(path).0);
+        }
+
+        Ok(())
+    }
+
+    /// Save a preset. If it is open, fail with a `PresetConflict` if its file was
+    /// changed elsewhere since it was opened or last saved.
+    pub fn save_preset(&self, app: &AppHandle, name: String, spec: PresetSpec) -> Result<()> {
+        self.write_preset(app, name, spec, true, None)
+    }
+
+    /// Save a preset, failing with a `PresetConflict` unless its file is at `expected`.
+    pub(crate) fn save_preset_expecting(
+        &self,
+        app: &AppHandle,
+        name: String,
+        spec: PresetSpec,
+        expected: &ExpectedRevision,
+    ) -> Result<()> {
+        self.write_preset(app, name, spec, true, Some(expected))
+    }
+
+    /// Save without a snapshot or a metadata update, for autosave and the save on exit,
+    /// so frequent automatic saves do not push explicit ones out of the version history.
+    pub(crate) fn autosave_preset(
+        &self,
+        app: &AppHandle,
+        name: String,
+        spec: PresetSpec,
+    ) -> Result<()> {
+        self.write_preset(app, name, spec, false, None)
+    }
+
+    /// Write a preset file. Without `expected`, an open preset is checked against
+    /// the revision it was opened or last saved at.
+    fn write_preset(
+        &self,
+        app: &AppHandle,
+        name: String,
+        spec: PresetSpec,
+        explicit: bool,
+        expected: Option<&ExpectedRevision>,
+    ) -> Result<()> {
+        metadata::ensure_preset_writable(app, &name)?;
+        let loaded = match expected {
+            Some(_) => None,
+            None => self.loaded_revision(&name),
+        };
+        if let Some(expected) = expected.or(loaded.as_ref()) {
+            check_preset_revision(app, &name, expected)?;
+        }
+        let preset_path = preset_path(app, &name)?;
 
         // Ensure the parent directory exists
         let parent_path = preset_path.parent().context("no parent path")?;
@@ -326,12 +706,90 @@ impl ModularAgentApp {
             std::fs::create_dir_all(parent_path)?;
         }
 
-        let json = spec.to_json()?;
-        std::fs::write(preset_path, json).with_context(|| "Failed to write preset file")?;
+        let is_new = !preset_path.exists();
+        let json = schema::stamp_version(&spec.to_json()?)?;
+        write_preset_file(app, &preset_path, &json).with_context(|| "Failed to write preset file")?;
+        search::index_preset(app, &name, &spec);
+        if explicit {
+            if let Err(e) = metadata::touch_preset(app, &name, is_new) {
+                log::warn!("Failed to update metadata of preset {}: {}", name, e);
+            }
+        }
+        if let Some(id) = self.get_preset_id(&name) {
+            self.set_saved_spec(&id, &spec);
+            self.record_revision(app, &id, &name);
+            autosave::mark_saved(app, &id, &name);
+        }
+        if is_new {
+            tree::invalidate(app);
+        }
+
+        // Keep a snapshot of every explicit save
+        if explicit {
+            if let Err(e) = versions::snapshot(app, &name, &json) {
+                log::warn!("Failed to save a snapshot of preset {}: {}", name, e);
+            }
+        }
+
         Ok(())
     }
 
-    pub async fn import_preset(&self, path: String, target_dir: String) -> Result<String> {
+    /// Restore a saved snapshot into the preset file.
+    /// If the preset is open, it is reloaded and its new ID is returned;
+    /// `ma:preset_reloaded` tells open editors to switch from the old ID.
+    pub async fn restore_preset_version(
+        &self,
+        app: &AppHandle,
+        name: &str,
+        version: &str,
+    ) -> Result<Option<String>> {
+        let spec = versions::read_version(app, name, version)?;
+
+        // Block restoring running presets
+        let old_id = self.get_preset_id(name);
+        if let Some(id) = &old_id {
+            let infos = self.ma.get_preset_infos().await;
+            if infos.iter().any(|p| &p.id == id && p.running) {
+                bail!("Cannot restore a running preset. Stop it first.");
+            }
+        }
+
+        // Restoring is a deliberate overwrite of the file
+        let is_new = !preset_path_exists(app, name);
+        self.save_preset_expecting(app, name.to_string(), spec, &ExpectedRevision::default())?;
+        if is_new {
+            let _ = app.emit(
+                EMIT_PRESET_LIST_CHANGED,
+                PresetListChangedPayload {
+                    path: parent_preset_path(name),
+                },
+            );
+        }
+
+        // Reload the live preset from the restored file
+        let Some(old_id) = old_id else {
+            return Ok(None);
+        };
+        self.ma.remove_preset(&old_id).await?;
+        {
+            let mut presets = self.presets.lock().unwrap();
+            presets.remove(name);
+        }
+        let id = self.open_preset(app, name.to_string()).await?;
+        let _ = app.emit(EMIT_PRESET_RELOADED, PresetReloadedPayload {
+            id: old_id,
+            new_id: id.clone(),
+        });
+
+        Ok(Some(id))
+    }
+
+    pub async fn import_preset(
+        &self,
+        app: &AppHandle,
+        path: String,
+        target_dir: String,
+    ) -> Result<String> {
         let path_buf = PathBuf::from(&path);
         let file_stem = path_buf
             .file_stem()
@@ -339,40 +797,118 @@ impl ModularAgentApp {
             .to_string_lossy()
             .to_string();
 
-        let base_name = if target_dir.is_empty() {
-            file_stem
-        } else {
-            format!("{}/{}", target_dir, file_stem)
-        };
+        self.import_preset_content(app, &file_stem, &target_dir, || {
+            std::fs::read_to_string(&path).with_context(|| format!("Failed to read file: {}", path))
+        })
+        .await
+    }
+
+    /// Import a preset from JSON text, e.g. pasted from the clipboard.
+    pub async fn import_preset_from_text(
+        &self,
+        app: &AppHandle,
+        text: String,
+        name: String,
+        target_dir: String,
+    ) -> Result<String> {
+        self.import_preset_content(app, name.trim(), &target_dir, || Ok(text))
+            .await
+    }
+
+    /// Validate, save and open an imported preset.
+    /// `read_content` is only called after the name has been validated.
+    async fn import_preset_content(
+        &self,
+        app: &AppHandle,
+        basename: &str,
+        target_dir: &str,
+        read_content: impl FnOnce() -> Result<String>,
+    ) -> Result<String> {
+        let base_name = join_preset_path(target_dir, basename);
 
         // Validate before any file I/O (prevents path traversal via target_dir)
-        if !is_valid_preset_name(&base_name) {
+        if !is_valid_basename(basename) || !is_valid_preset_name(app, &base_name) {
             return Err(anyhow!("Invalid preset name: {}", base_name));
         }
 
-        let name = unique_preset_name(&base_name);
+        let name = unique_preset_name(app, &base_name);
 
-        // Read and validate the imported file
-        let content = std::fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read file: {}", path))?;
+        // Read, upgrade and validate the imported content
+        let mut content = read_content()?;
+        let migrated = schema::migrate_json(&content)?.map(|(json, report)| {
+            content = json;
+            report
+        });
         let spec = PresetSpec::from_json(&content)
             .map_err(|e| anyhow!("Failed to parse preset: {}", e))?;
 
         // Save to local presets directory
-        self.save_preset(name.clone(), spec)?;
+        self.save_preset(app, name.clone(), spec)?;
 
-        // Open the preset; clean up orphaned file on failure
-        match self.open_preset(name.clone()).await {
-            Ok(id) => Ok(id),
+        // Open the preset; clean up the orphaned file, its sidecars and snapshot on failure
+        match self.open_preset(app, name.clone()).await {
+            Ok(id) => {
+                if let Some(report) = migrated {
+                    log::info!("Imported preset {} was migrated: {:?}", name, report);
+                    emit_preset_migrated(app, &id, &name, report);
+                }
+                Ok(id)
+            }
             Err(e) => {
-                if let Ok(p) = preset_path(&name) {
+                if let Ok(p) = preset_path(app, &name) {
+                    if let Some(dir) = p.parent() {
+                        let file_name = format!("{}.json", preset_basename(&name));
+                        for (sidecar, _) in preset_sidecars(dir, &file_name) {
+                            let _ = std::fs::remove_file(sidecar);
+                        }
+                    }
                     let _ = std::fs::remove_file(p);
                 }
+                versions::remove_versions(&name);
+                search::remove_preset(app, &name);
+                tree::invalidate(app);
                 Err(e)
             }
         }
     }
 
+    /// Copy a preset into `target_dir` under a free name.
+    /// If the source is open, its in-memory spec (including unsaved edits) is copied.
+    pub async fn duplicate_preset(
+        &self,
+        app: &AppHandle,
+        name: &str,
+        target_dir: &str,
+        open: bool,
+    ) -> Result<DuplicatedPreset> {
+        if !is_valid_preset_name(app, name) {
+            bail!("Invalid preset name: {}", name);
+        }
+
+        let base_name = join_preset_path(target_dir, preset_basename(name));
+
+        // Validate before any file I/O (prevents path traversal via target_dir)
+        if !is_valid_preset_name(app, &base_name) {
+            bail!("Invalid preset name: {}", base_name);
+        }
+
+        let spec = self.get_preset_spec(app, name).await?;
+
+        let new_name = unique_preset_name(app, &base_name);
+        self.save_preset(app, new_name.clone(), spec)?;
+        if let Err(e) = metadata::copy_preset_metadata(app, name, &new_name) {
+            log::warn!("Failed to copy metadata of preset {}: {}", name, e);
+        }
+
+        let id = if open {
+            Some(self.open_preset(app, new_name.clone()).await?)
+        } else {
+            None
+        };
+
+        Ok(DuplicatedPreset { name: new_name, id })
+    }
+
     pub async fn start_preset(&self, preset_id: &str) -> Result<()> {
         self.ma.start_preset(preset_id).await?;
         Ok(())
@@ -383,6 +919,34 @@ impl ModularAgentApp {
         Ok(())
     }
 
+    /// Fail with a summary if validation finds errors in an open preset.
+    pub(crate) async fn check_preset_errors(&self, app: &AppHandle, preset_id: &str) -> Result<()> {
+        let spec = self
+            .ma
+            .get_preset_spec(preset_id)
+            .await
+            .with_context(|| format!("Preset not found: {}", preset_id))?;
+        let errors: Vec<String> = validate::validate(app, &spec)
+            .into_iter()
+            .filter(|issue| issue.is_error())
+            .map(|issue| issue.to_string())
+            .collect();
+        if !errors.is_empty() {
+            bail!(
+                "Preset has {} error(s):\n{}",
+                errors.len(),
+                errors.join("\n")
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether an open preset is running.
+    pub(crate) async fn is_running(&self, preset_id: &str) -> bool {
+        let infos = self.ma.get_preset_infos().await;
+        infos.iter().any(|p| p.id == preset_id && p.running)
+    }
+
     /// Close a preset by ID (unload from memory, does NOT delete file).
     /// Only unloads if the preset is not running.
     /// Returns Ok(true) if unloaded, Ok(false) if still running.
@@ -412,13 +976,245 @@ impl ModularAgentApp {
         Ok(true)
     }
 
-    fn get_preset_id(&self, name: &str) -> Option<String> {
-        let presets = self.presets.lock().unwrap();
-        presets.get(name).cloned()
+    /// List a directory with metadata for each entry.
+    pub async fn get_dir_entry_infos(
+        &self,
+        app: &AppHandle,
+        path: &str,
+    ) -> Result<Vec<DirEntryInfo>> {
+        let mut entries = Vec::new();
+        let dir = folder_path(app, path)?;
+        if !dir.exists() || !dir.is_dir() {
+            return Ok(entries);
+        }
+
+        let running_ids: Vec<String> = self
+            .ma
+            .get_preset_infos()
+            .await
+            .into_iter()
+            .filter(|p| p.running)
+            .map(|p| p.id)
+            .collect();
+        let auto_start_presets = {
+            let core_settings = app.state::<Mutex<CoreSettings>>();
+            let settings = core_settings.lock().unwrap();
+            settings.auto_start_presets.clone()
+        };
+
+        let dir_entries =
+            std::fs::read_dir(&dir).with_context(|| format!("Failed to read directory: {:?}", dir))?;
+
+        for entry in dir_entries {
+            let entry_path = entry?.path();
+            let file_metadata = entry_path.metadata().ok();
+            let modified = file_metadata
+                .as_ref()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64);
+
+            if entry_path.is_dir() {
+                let dir_name = entry_path
+                    .file_name()
+                    .context("Failed to get directory name")?
+                    .to_string_lossy()
+                    .to_string();
+                if dir_name.starts_with('.') {
+                    continue;
+                }
+                let folder = join_preset_path(path, &dir_name);
+                entries.push(DirEntryInfo {
+                    kind: DirEntryKind::Folder,
+                    locked: metadata::read_folder_metadata(app, &folder).read_only,
+                    read_only: metadata::is_folder_read_only(app, &folder),
+                    metadata: None,
+                    path: folder,
+                    name: dir_name,
+                    modified,
+                    size: None,
+                    agent_count: None,
+                    open: false,
+                    running: false,
+                    auto_start: false,
+                });
+            } else if entry_path.is_file() && entry_path.extension().unwrap_or_default() == "json" {
+                let base_name = entry_path
+                    .file_stem()
+                    .context("Failed to get file stem")?
+                    .to_string_lossy()
+                    .trim()
+                    .to_string();
+                let name = join_preset_path(path, &base_name);
+                let agent_count = std::fs::read_to_string(&entry_path)
+                    .ok()
+                    .and_then(|s| PresetSpec::from_json(&s).ok())
+                    .map(|spec| spec.agents.len());
+                let id = self.get_preset_id(&name);
+                entries.push(DirEntryInfo {
+                    kind: DirEntryKind::Preset,
+                    modified,
+                    size: file_metadata.as_ref().map(|m| m.len()),
+                    agent_count,
+                    open: id.is_some(),
+                    running: id.is_some_and(|id| running_ids.contains(&id)),
+                    auto_start: auto_start_presets.contains(&name),
+                    locked: metadata::read_preset_metadata(app, &name).locked,
+                    read_only: metadata::is_preset_read_only(app, &name),
+                    metadata: metadata::preset_metadata(app, &name),
+                    name: base_name,
+                    path: name,
+                });
+            }
+        }
+
+        Ok(entries)
+    }
+
+    pub(crate) fn get_preset_id(&self, name: &str) -> Option<String> {
+        let presets = self.presets.lock().unwrap();
+        presets.get(name).cloned()
+    }
+
+    /// Names and IDs of the open presets.
+    pub(crate) fn open_presets(&self) -> Vec<(String, String)> {
+        let presets = self.presets.lock().unwrap();
+        presets
+            .iter()
+            .map(|(name, id)| (name.clone(), id.clone()))
+            .collect()
+    }
+
+    /// Record `spec` as the saved state of an open preset.
+    fn set_saved_spec(&self, id: &str, spec: &PresetSpec) {
+        let Ok(value) = serde_json::to_value(spec).map(comparable_spec) else {
+            return;
+        };
+        let mut saved_specs = self.saved_specs.lock().unwrap();
+        saved_specs.insert(id.to_string(), value);
+    }
+
+    /// Whether the in-memory spec of an open preset differs from the last saved one.
+    pub(crate) async fn is_dirty(&self, id: &str) -> bool {
+        let Some(spec) = self.ma.get_preset_spec(id).await else {
+            return false;
+        };
+        let Ok(current) = serde_json::to_value(&spec).map(comparable_spec) else {
+            return false;
+        };
+        let saved_specs = self.saved_specs.lock().unwrap();
+        saved_specs.get(id).is_some_and(|saved| saved != &current)
+    }
+
+    /// Replace the presets reported as dirty with `dirty` (ID and name pairs), and return
+    /// the presets whose reported state changed, with their new state.
+    pub(crate) fn update_reported_dirty(
+        &self,
+        dirty: &[(String, String)],
+    ) -> Vec<(String, String, bool)> {
+        let mut reported = self.reported_dirty.lock().unwrap();
+        let mut changed = Vec::new();
+        for (id, name) in dirty {
+            if reported.insert(id.clone(), name.clone()).is_none() {
+                changed.push((id.clone(), name.clone(), true));
+            }
+        }
+        // Presets that became clean without a save (e.g. undone edits) or were closed
+        reported.retain(|id, name| {
+            let keep = dirty.iter().any(|(dirty_id, _)| dirty_id == id);
+            if !keep {
+                changed.push((id.clone(), name.clone(), false));
+            }
+            keep
+        });
+        changed
+    }
+
+    /// Stop reporting a preset as dirty, returning whether it was.
+    pub(crate) fn clear_reported_dirty(&self, id: &str) -> bool {
+        let mut reported = self.reported_dirty.lock().unwrap();
+        reported.remove(id).is_some()
+    }
+
+    /// Record that autosave skipped a preset, returning false if it already had.
+    pub(crate) fn record_autosave_conflict(&self, id: &str) -> bool {
+        let mut conflicts = self.autosave_conflicts.lock().unwrap();
+        conflicts.insert(id.to_string())
+    }
+
+    pub(crate) fn clear_autosave_conflict(&self, id: &str) {
+        let mut conflicts = self.autosave_conflicts.lock().unwrap();
+        conflicts.remove(id);
+    }
+
+    /// Forget the saved specs and revisions of presets that are no longer open.
+    pub(crate) fn prune_saved_specs(&self) {
+        let ids: Vec<String> = self.open_presets().into_iter().map(|(_, id)| id).collect();
+        {
+            let mut saved_specs = self.saved_specs.lock().unwrap();
+            saved_specs.retain(|id, _| ids.contains(id));
+        }
+        let mut revisions = self.revisions.lock().unwrap();
+        revisions.retain(|id, _| ids.contains(id));
+    }
+
+    /// Record the current revision of the file of an open preset.
+    fn record_revision(&self, app: &AppHandle, id: &str, name: &str) {
+        let mut revisions = self.revisions.lock().unwrap();
+        match preset_revision(app, name) {
+            Ok(Some(revision)) => {
+                revisions.insert(id.to_string(), revision);
+            }
+            _ => {
+                revisions.remove(id);
+            }
+        }
+    }
+
+    /// Expected revision for saving an open preset: its file when it was opened or last saved.
+    /// Only the hash is compared, as moving a file to another filesystem changes its
+    /// modification time.
+    fn loaded_revision(&self, name: &str) -> Option<ExpectedRevision> {
+        let id = self.get_preset_id(name)?;
+        let revision = self.revisions.lock().unwrap().get(&id).cloned()?;
+        Some(ExpectedRevision {
+            hash: Some(revision.hash),
+            mtime: None,
+        })
+    }
+
+    /// Spec of a preset by name: the in-memory spec (including unsaved edits)
+    /// if it is open, otherwise the saved file.
+    pub(crate) async fn get_preset_spec(&self, app: &AppHandle, name: &str) -> Result<PresetSpec> {
+        if self.get_preset_id(name).is_some() {
+            self.get_open_preset_spec(name).await
+        } else {
+            read_preset_file(app, name)
+        }
+    }
+
+    /// In-memory spec of an open preset.
+    pub(crate) async fn get_open_preset_spec(&self, name: &str) -> Result<PresetSpec> {
+        let id = self
+            .get_preset_id(name)
+            .with_context(|| format!("Preset is not open: {}", name))?;
+        self.ma
+            .get_preset_spec(&id)
+            .await
+            .with_context(|| format!("Preset not found: {}", name))
     }
 }
 
 pub fn init(app: &AppHandle) -> Result<()> {
+    let settings_data_dir = {
+        let core_settings = app.state::<Mutex<CoreSettings>>();
+        let settings = core_settings.lock().unwrap();
+        settings.data_dir.clone()
+    };
+    let data_dir = resolve_data_dir(settings_data_dir.as_deref())?;
+    log::info!("Data directory: {:?}", data_dir);
+    let _ = DATA_DIR.set(data_dir);
+
     let ma = app.ma();
     let asapp = ModularAgentApp::new(ma);
     app.manage(asapp);
@@ -429,15 +1225,20 @@ pub async fn ready(app: &AppHandle) -> Result<()> {
     let asapp = app.state::<ModularAgentApp>();
     let ma = &asapp.ma;
     start_modular_agent_observer(&ma, app.clone());
+    autosave::start(app.clone());
+
+    start_mcp_services(app).await?;
 
-    start_mcp_services().await?;
+    trash::purge_expired(app).unwrap_or_else(|e| {
+        log::error!("Failed to purge expired trash: {}", e);
+    });
 
     run_auto_start_presets(app).await;
 
     Ok(())
 }
 
-async fn start_mcp_services() -> Result<()> {
+async fn start_mcp_services(app: &AppHandle) -> Result<()> {
     let modular_agent_dir = modular_agent_dir()?;
     let mcp_path = modular_agent_dir.join("mcp.json");
     if !mcp_path.exists() {
@@ -446,9 +1247,10 @@ async fn start_mcp_services() -> Result<()> {
 
     let tools = register_tools_from_mcp_json(mcp_path).await?;
     log::info!("Registered {} tools:", tools.len());
-    for tool in tools {
+    for tool in &tools {
         log::info!("  - {}", tool);
     }
+    validate::set_mcp_tools(app, tools);
 
     Ok(())
 }
@@ -463,7 +1265,7 @@ async fn run_auto_start_presets(app: &AppHandle) {
     let asapp = app.state::<ModularAgentApp>();
     for name in auto_start_presets {
         log::info!("Auto-starting preset: {}", name);
-        match asapp.open_preset(name.clone()).await {
+        match asapp.open_preset(app, name.clone()).await {
             Ok(id) => {
                 if let Err(e) = asapp.start_preset(&id).await {
                     log::error!("Failed to start preset {}: {}", name, e);
@@ -479,13 +1281,62 @@ async fn run_auto_start_presets(app: &AppHandle) {
 
 pub fn quit(_app: &AppHandle) {}
 
-fn modular_agent_dir() -> Result<PathBuf> {
-    let home_dir = dirs::home_dir().with_context(|| "Failed to get home directory")?;
-    let modular_agent_dir = home_dir.join(MODULAR_AGENT_PATH);
-    Ok(modular_agent_dir)
+pub(crate) fn modular_agent_dir() -> Result<PathBuf> {
+    if let Some(dir) = DATA_DIR.get() {
+        return Ok(dir.clone());
+    }
+    resolve_data_dir(None)
+}
+
+/// Resolve the data directory. In order of precedence: the `--data-dir` flag,
+/// the `MODULAR_AGENT_HOME` environment variable, the `data_dir` setting,
+/// and the default of the data layout (`~/.modular_agent` unless XDG or portable).
+fn resolve_data_dir(settings_data_dir: Option<&str>) -> Result<PathBuf> {
+    let Some(dir) = data_dir_override(settings_data_dir) else {
+        return layout::default_data_dir();
+    };
+
+    // Expand a leading '~' and make relative paths absolute
+    let dir = match dir.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .with_context(|| "Failed to get home directory")?
+            .join(rest),
+        None => PathBuf::from(dir),
+    };
+    std::path::absolute(&dir).with_context(|| format!("Invalid data directory: {:?}", dir))
+}
+
+/// Data directory set by the `--data-dir` flag, the `MODULAR_AGENT_HOME`
+/// environment variable or the `data_dir` setting, in that order.
+pub(crate) fn data_dir_override(settings_data_dir: Option<&str>) -> Option<String> {
+    data_dir_from_args()
+        .or_else(|| {
+            std::env::var(MODULAR_AGENT_HOME_ENV)
+                .ok()
+                .filter(|s| !s.trim().is_empty())
+        })
+        .or_else(|| {
+            settings_data_dir
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| s.to_string())
+        })
 }
 
-fn presets_dir() -> Result<PathBuf> {
+/// Value of `--data-dir <path>` or `--data-dir=<path>`, if given.
+fn data_dir_from_args() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == DATA_DIR_ARG {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(DATA_DIR_ARG).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+pub(crate) fn presets_dir() -> Result<PathBuf> {
     let modular_agent_dir = modular_agent_dir()?;
     let presets_dir = modular_agent_dir.join(MODULAR_AGENT_PRESETS_PATH);
     Ok(presets_dir)
@@ -493,8 +1344,10 @@ fn presets_dir() -> Result<PathBuf> {
 
 // Get the file path for an preset based on its name.
 // '/' in the name indicates subdirectories.
-fn preset_path(preset_name: &str) -> Result<PathBuf> {
-    let mut preset_path = presets_dir()?;
+// A "workspace:" prefix selects the workspace root instead of the presets directory.
+pub(crate) fn preset_path(app: &AppHandle, preset_name: &str) -> Result<PathBuf> {
+    let (workspace, preset_name) = split_workspace(preset_name);
+    let mut preset_path = workspace_root(app, workspace)?;
 
     let path_components: Vec<&str> = preset_name.split('/').collect();
     for &component in &path_components[..path_components.len()] {
@@ -506,34 +1359,354 @@ fn preset_path(preset_name: &str) -> Result<PathBuf> {
     Ok(preset_path)
 }
 
-fn preset_path_exists(name: &str) -> bool {
-    preset_path(name).map(|p| p.exists()).unwrap_or(false)
+/// Get the directory of a folder path, e.g. "Category" or "team:Category".
+/// An empty path (or just "team:") is the root of the workspace.
+pub(crate) fn folder_path(app: &AppHandle, path: &str) -> Result<PathBuf> {
+    let (workspace, rel) = split_workspace(path);
+    if rel.starts_with('/') || rel.contains("..") {
+        bail!("Invalid path: {}", path);
+    }
+    let root = workspace_root(app, workspace)?;
+    if rel.is_empty() {
+        return Ok(root);
+    }
+    Ok(root.join(rel))
+}
+
+/// Move a file or directory, falling back to copy and delete when `to` is on
+/// another filesystem (e.g. moving between workspaces).
+pub(crate) fn move_path(from: &Path, to: &Path) -> Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if from.is_dir() {
+        copy_dir_recursive(from, to)?;
+        std::fs::remove_dir_all(from).with_context(|| format!("Failed to remove {:?}", from))?;
+    } else {
+        std::fs::copy(from, to).with_context(|| format!("Failed to copy {:?}", from))?;
+        std::fs::remove_file(from).with_context(|| format!("Failed to remove {:?}", from))?;
+    }
+    Ok(())
+}
+
+/// Copy a file or directory.
+pub(crate) fn copy_path(from: &Path, to: &Path) -> Result<()> {
+    if from.is_dir() {
+        copy_dir_recursive(from, to)
+    } else {
+        std::fs::copy(from, to).with_context(|| format!("Failed to copy {:?}", from))?;
+        Ok(())
+    }
+}
+
+fn copy_dir_recursive(from: &Path, to: &Path) -> Result<()> {
+    std::fs::create_dir_all(to).with_context(|| format!("Failed to create {:?}", to))?;
+    let dir_entries =
+        std::fs::read_dir(from).with_context(|| format!("Failed to read directory: {:?}", from))?;
+    for entry in dir_entries {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_dir_recursive(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {:?}", entry.path()))?;
+        }
+    }
+    Ok(())
+}
+
+fn emit_preset_migrated(app: &AppHandle, id: &str, name: &str, report: MigrationReport) {
+    let _ = app.emit(
+        EMIT_PRESET_MIGRATED,
+        PresetMigratedPayload {
+            id: id.to_string(),
+            name: name.to_string(),
+            report,
+        },
+    );
+}
+
+/// FNV-1a hash of a file content. Stable across runs and platforms.
+fn content_hash(content: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Current revision of a preset file, or `None` if it does not exist.
+pub(crate) fn preset_revision(app: &AppHandle, name: &str) -> Result<Option<PresetRevision>> {
+    let path = preset_path(app, name)?;
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content =
+        std::fs::read(&path).with_context(|| format!("Failed to read preset file: {}", name))?;
+    let mtime = path
+        .metadata()
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    Ok(Some(PresetRevision {
+        hash: content_hash(&content),
+        mtime,
+    }))
+}
+
+/// Fail with a `PresetConflict` if the preset file is not at the expected revision.
+fn check_preset_revision(app: &AppHandle, name: &str, expected: &ExpectedRevision) -> Result<()> {
+    if expected.hash.is_none() && expected.mtime.is_none() {
+        return Ok(());
+    }
+    let current = preset_revision(app, name)?;
+    let matches = current.as_ref().is_some_and(|current| {
+        expected.hash.as_ref().is_none_or(|h| h == &current.hash)
+            && expected.mtime.is_none_or(|m| m == current.mtime)
+    });
+    if matches {
+        return Ok(());
+    }
+
+    let (message, disk_spec) = match &current {
+        Some(_) => {
+            let disk_spec = read_preset_file(app, name)
+                .inspect_err(|e| log::warn!("Failed to read conflicting preset {}: {}", name, e))
+                .ok();
+            (format!("Preset was changed on disk: {}", name), disk_spec)
+        }
+        None => (format!("Preset was deleted on disk: {}", name), None),
+    };
+    Err(PresetConflict {
+        kind: "conflict",
+        message,
+        revision: current,
+        disk_spec,
+    }
+    .into())
+}
+
+/// Backup of the previous version of a preset file, e.g. "MyPreset.json.bak".
+fn preset_backup_path(path: &Path) -> PathBuf {
+    path.with_extension("json.bak")
+}
+
+/// Whether `suffix` names a file kept next to a preset file: its backup (".bak"),
+/// metadata (".meta"), set-aside broken file (".corrupt") or pre-migration copy (".v0.bak").
+fn is_preset_sidecar_suffix(suffix: &str) -> bool {
+    match suffix {
+        ".bak" | ".meta" | ".corrupt" => true,
+        _ => suffix
+            .strip_prefix(".v")
+            .and_then(|s| s.strip_suffix(".bak"))
+            .is_some_and(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit())),
+    }
+}
+
+/// Files kept next to the preset file `file_name` in `dir`, with their suffixes,
+/// e.g. "MyPreset.json.bak" -> ".bak". They move along with the preset.
+fn preset_sidecars(dir: &Path, file_name: &str) -> Vec<(PathBuf, String)> {
+    let Ok(dir_entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    dir_entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let suffix = name.strip_prefix(file_name)?;
+            (is_preset_sidecar_suffix(suffix) && path.is_file())
+                .then(|| (path.clone(), suffix.to_string()))
+        })
+        .collect()
+}
+
+/// Move the sidecars of preset file `from` next to preset file `to`.
+pub(crate) fn move_preset_sidecars(from: &Path, to: &Path) {
+    let (Some(from_dir), Some(from_name), Some(to_name)) =
+        (from.parent(), from.file_name(), to.file_name())
+    else {
+        return;
+    };
+    let from_name = from_name.to_string_lossy();
+    let to_name = to_name.to_string_lossy();
+    for (sidecar, suffix) in preset_sidecars(from_dir, &from_name) {
+        let target = to.with_file_name(format!("{}{}", to_name, suffix));
+        if let Err(e) = move_path(&sidecar, &target) {
+            log::warn!("Failed to move preset sidecar {:?}: {}", sidecar, e);
+        }
+    }
+}
+
+/// Write a preset file atomically.
+///
+/// The content is written to a temporary file, flushed to disk, and renamed over
+/// the target, so a crash never leaves a truncated file behind. The previous file
+/// is kept as a `.bak` as long as it is a valid preset.
+fn write_preset_file(app: &AppHandle, path: &Path, json: &str) -> Result<()> {
+    watcher::mark_self_write(app, path);
+
+    let tmp_path = path.with_extension("json.tmp");
+    let write_tmp = || -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()
+    };
+    if let Err(e) = write_tmp() {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e).with_context(|| format!("Failed to write temporary file: {:?}", tmp_path));
+    }
+
+    // Keep the previous file as a backup, unless it is already broken
+    if path.exists() {
+        let is_valid = std::fs::read_to_string(path)
+            .map(|s| PresetSpec::from_json(&s).is_ok())
+            .unwrap_or(false);
+        if is_valid {
+            if let Err(e) = std::fs::copy(path, preset_backup_path(path)) {
+                log::warn!("Failed to back up preset file {:?}: {}", path, e);
+            }
+        }
+    }
+
+    if let Err(e) = std::fs::rename(&tmp_path, path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e).with_context(|| format!("Failed to replace preset file: {:?}", path));
+    }
+
+    // Make the rename itself durable
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = std::fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+
+    Ok(())
+}
+
+/// Restore a preset file from its backup if it cannot be parsed.
+/// The broken file is kept as a `.corrupt` file for inspection.
+/// Returns the parse error of the broken file if it was repaired.
+fn repair_preset_file(app: &AppHandle, path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read preset file: {:?}", path))?;
+    let error = match PresetSpec::from_json(&content) {
+        Ok(_) => return Ok(None),
+        Err(e) => e.to_string(),
+    };
+
+    let backup = std::fs::read_to_string(preset_backup_path(path))
+        .ok()
+        .filter(|s| PresetSpec::from_json(s).is_ok());
+    let Some(backup) = backup else {
+        bail!("Failed to parse preset and no valid backup is available: {}", error);
+    };
+
+    let corrupt_path = path.with_extension("json.corrupt");
+    watcher::mark_self_write(app, path);
+    std::fs::rename(path, &corrupt_path)
+        .with_context(|| format!("Failed to set aside broken preset file: {:?}", path))?;
+    write_preset_file(app, path, &backup)?;
+
+    Ok(Some(error))
+}
+
+/// Spec JSON for dirty tracking. The viewport is left out, as panning is not an edit.
+fn comparable_spec(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(preset) = value.as_object_mut() {
+        preset.remove("viewport");
+    }
+    value
+}
+
+/// Read and parse a saved preset file.
+pub(crate) fn read_preset_file(app: &AppHandle, name: &str) -> Result<PresetSpec> {
+    let path = preset_path(app, name)?;
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read preset file: {}", name))?;
+    PresetSpec::from_json(&content).map_err(|e| anyhow!("Failed to parse preset: {}", e))
+}
+
+pub(crate) fn preset_path_exists(app: &AppHandle, name: &str) -> bool {
+    preset_path(app, name).map(|p| p.exists()).unwrap_or(false)
+}
+
+pub(crate) fn unique_preset_name(app: &AppHandle, base_name: &str) -> String {
+    unique_preset_name_avoiding(app, base_name, |_| false)
 }
 
-fn unique_preset_name(base_name: &str) -> String {
-    if !preset_path_exists(base_name) {
+/// Like `unique_preset_name`, also avoiding names for which `is_taken` is true,
+/// e.g. names planned for other presets in the same batch.
+pub(crate) fn unique_preset_name_avoiding(
+    app: &AppHandle,
+    base_name: &str,
+    is_taken: impl Fn(&str) -> bool,
+) -> String {
+    let is_free = |name: &str| !preset_path_exists(app, name) && !is_taken(name);
+    if is_free(base_name) {
         return base_name.to_string();
     }
     let copy_name = format!("{} copy", base_name);
-    if !preset_path_exists(&copy_name) {
+    if is_free(&copy_name) {
         return copy_name;
     }
     for i in 2.. {
         let name = format!("{} copy {}", base_name, i);
-        if !preset_path_exists(&name) {
+        if is_free(&name) {
             return name;
         }
     }
     unreachable!()
 }
 
-fn get_dir_entries(path: &str) -> Result<Vec<String>> {
-    if path.starts_with("/") || path.contains("..") {
-        bail!("Invalid path: {}", path);
+/// List the names of all presets under `path`, recursively.
+/// Hidden files and folders are skipped.
+pub(crate) fn list_presets_recursive(app: &AppHandle, path: &str) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let dir = folder_path(app, path)?;
+    if dir.is_dir() {
+        collect_preset_names(&dir, path, &mut names)?;
+    }
+    names.sort();
+    Ok(names)
+}
+
+pub(crate) fn collect_preset_names(dir: &Path, prefix: &str, names: &mut Vec<String>) -> Result<()> {
+    let dir_entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read directory: {:?}", dir))?;
+    for entry in dir_entries {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if file_name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            let sub_prefix = join_preset_path(prefix, &file_name);
+            collect_preset_names(&path, &sub_prefix, names)?;
+        } else if path.is_file() && path.extension().unwrap_or_default() == "json" {
+            let stem = path
+                .file_stem()
+                .context("Failed to get file stem")?
+                .to_string_lossy()
+                .to_string();
+            names.push(join_preset_path(prefix, &stem));
+        }
     }
+    Ok(())
+}
+
+fn get_dir_entries(app: &AppHandle, path: &str) -> Result<Vec<String>> {
     let mut entries = Vec::new();
-    let preset_dir = presets_dir()?;
-    let dir = preset_dir.join(path);
+    let dir = folder_path(app, path)?;
     if !dir.exists() || !dir.is_dir() {
         return Ok(entries);
     }
@@ -564,7 +1737,51 @@ fn get_dir_entries(path: &str) -> Result<Vec<String>> {
     Ok(entries)
 }
 
-fn is_valid_preset_name(new_name: &str) -> bool {
+/// Create a folder that is kept even while it has no presets.
+fn create_folder(app: &AppHandle, path: &str) -> Result<()> {
+    if !is_valid_preset_name(app, path) {
+        bail!("Invalid folder path: {}", path);
+    }
+
+    let dir = folder_path(app, path)?;
+    if dir.exists() || preset_path_exists(app, path) {
+        bail!("A folder or preset with this name already exists: {}", path);
+    }
+    metadata::ensure_folder_writable(app, &parent_preset_path(path))?;
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create folder: {}", path))?;
+    std::fs::write(dir.join(FOLDER_KEEP_FILE), "")
+        .with_context(|| format!("Failed to mark folder as kept: {}", path))?;
+    tree::invalidate(app);
+
+    // Emit list changed for every ancestor, as intermediate folders may have been created
+    emit_ancestors_list_changed(app, path);
+
+    Ok(())
+}
+
+/// Emit list changed for every ancestor folder of `path`, up to the workspace root.
+pub(crate) fn emit_ancestors_list_changed(app: &AppHandle, path: &str) {
+    let root = workspace_prefix(split_workspace(path).0);
+    let mut parent = path.to_string();
+    while parent != root {
+        parent = parent_preset_path(&parent);
+        let _ = app.emit(
+            EMIT_PRESET_LIST_CHANGED,
+            PresetListChangedPayload {
+                path: parent.clone(),
+            },
+        );
+    }
+}
+
+pub(crate) fn is_valid_preset_name(app: &AppHandle, new_name: &str) -> bool {
+    // Names may be qualified by a configured workspace
+    let (workspace, new_name) = split_workspace(new_name);
+    if !workspace::is_known_workspace(app, workspace) {
+        return false;
+    }
+
     // Check if the name is empty
     if new_name.trim().is_empty() {
         return false;
@@ -596,8 +1813,13 @@ fn is_valid_preset_name(new_name: &str) -> bool {
     true
 }
 
-/// Remove empty directories walking up from `start_dir` toward `presets_root`.
-fn cleanup_empty_ancestors(app: &AppHandle, start_dir: &std::path::Path, presets_root: &std::path::Path) {
+/// Remove empty directories walking up from `start_dir` toward the root of `workspace`.
+/// Folders marked with a `.keep` file are never empty and are left alone.
+fn cleanup_empty_ancestors(app: &AppHandle, start_dir: &std::path::Path, workspace: &str) {
+    let Ok(presets_root) = workspace_root(app, workspace) else {
+        return;
+    };
+    let presets_root = presets_root.as_path();
     let mut dir = start_dir.to_path_buf();
     while dir != *presets_root && dir.starts_with(presets_root) {
         let is_empty = dir
@@ -617,6 +1839,7 @@ fn cleanup_empty_ancestors(app: &AppHandle, start_dir: &std::path::Path, presets
                 .parent()
                 .map(|p| p.to_string_lossy().replace('\\', "/"))
                 .unwrap_or_default();
+            let parent_path = format!("{}{}", workspace_prefix(workspace), parent_path);
             let _ = app.emit(
                 EMIT_PRESET_LIST_CHANGED,
                 PresetListChangedPayload { path: parent_path },
@@ -663,6 +1886,21 @@ fn update_auto_start_presets_prefix(app: &AppHandle, old_prefix: &str, new_prefi
     }
 }
 
+/// Update auto_start_presets: remove entries under a deleted folder.
+fn remove_auto_start_presets_prefix(app: &AppHandle, prefix: &str) {
+    let core_settings = app.state::<Mutex<CoreSettings>>();
+    let mut settings = core_settings.lock().unwrap();
+    let len = settings.auto_start_presets.len();
+    settings
+        .auto_start_presets
+        .retain(|entry| !entry.starts_with(prefix));
+    let changed = settings.auto_start_presets.len() != len;
+    drop(settings);
+    if changed {
+        let _ = crate::modular_agent_desktop::settings::save(app);
+    }
+}
+
 #[tauri::command]
 pub fn new_preset_with_name_cmd(
     app: AppHandle,
@@ -671,13 +1909,13 @@ pub fn new_preset_with_name_cmd(
 ) -> Result<String, String> {
     let parent_dir = parent_preset_path(&name);
     let parent_existed =
-        parent_dir.is_empty() || presets_dir().map(|d| d.join(&parent_dir).exists()).unwrap_or(true);
+        parent_dir.is_empty() || folder_path(&app, &parent_dir).map(|d| d.exists()).unwrap_or(true);
     let id = asapp
-        .new_preset_with_name(name.clone())
+        .new_preset_with_name(&app, name.clone())
         .map_err(|e| e.to_string())?;
     // Save empty preset to disk immediately so it appears in the sidebar
     asapp
-        .save_preset(name.clone(), PresetSpec::default())
+        .save_preset(&app, name.clone(), PresetSpec::default())
         .map_err(|e| e.to_string())?;
     let _ = app.emit(
         EMIT_PRESET_LIST_CHANGED,
@@ -722,6 +1960,49 @@ pub async fn move_folder_cmd(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn rename_preset_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    name: String,
+    new_name: String,
+) -> Result<(), String> {
+    asapp
+        .rename_preset(&app, &name, &new_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rename_folder_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    path: String,
+    new_name: String,
+) -> Result<(), String> {
+    asapp
+        .rename_folder(&app, &path, &new_name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_folder_cmd(app: AppHandle, path: String) -> Result<(), String> {
+    create_folder(&app, &path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_folder_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    path: String,
+) -> Result<(), String> {
+    asapp
+        .delete_folder(&app, &path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn delete_preset_cmd(
     app: AppHandle,
@@ -729,7 +2010,7 @@ pub async fn delete_preset_cmd(
     name: String,
 ) -> Result<(), String> {
     asapp
-        .delete_preset(&name)
+        .delete_preset(&app, &name)
         .await
         .map_err(|e| e.to_string())?;
     let _ = app.emit(
@@ -741,20 +2022,29 @@ pub async fn delete_preset_cmd(
     Ok(())
 }
 
+/// Save a preset and return its new revision.
+/// Fail with a conflict if the file changed since the caller loaded it: since `expected`
+/// if given, otherwise since the preset was opened or last saved.
 #[tauri::command]
 pub fn save_preset_cmd(
     app: AppHandle,
     asapp: State<'_, ModularAgentApp>,
     name: String,
     spec: PresetSpec,
-) -> Result<(), String> {
-    let is_new = !preset_path_exists(&name);
+    expected: Option<ExpectedRevision>,
+) -> Result<Option<PresetRevision>, SavePresetError> {
+    let is_new = !preset_path_exists(&app, &name);
     let parent_dir = parent_preset_path(&name);
     let parent_existed =
-        parent_dir.is_empty() || presets_dir().map(|d| d.join(&parent_dir).exists()).unwrap_or(true);
-    asapp
-        .save_preset(name.clone(), spec)
-        .map_err(|e| e.to_string())?;
+        parent_dir.is_empty() || folder_path(&app, &parent_dir).map(|d| d.exists()).unwrap_or(true);
+    let saved = match &expected {
+        Some(expected) => asapp.save_preset_expecting(&app, name.clone(), spec, expected),
+        None => asapp.save_preset(&app, name.clone(), spec),
+    };
+    saved.map_err(|e| match e.downcast::<PresetConflict>() {
+        Ok(conflict) => SavePresetError::Conflict(Box::new(conflict)),
+        Err(e) => SavePresetError::Failed(e.to_string()),
+    })?;
     if is_new {
         let _ = app.emit(
             EMIT_PRESET_LIST_CHANGED,
@@ -771,7 +2061,15 @@ pub fn save_preset_cmd(
             );
         }
     }
-    Ok(())
+    preset_revision(&app, &name).map_err(|e| SavePresetError::Failed(e.to_string()))
+}
+
+#[tauri::command]
+pub fn get_preset_revision_cmd(
+    app: AppHandle,
+    name: String,
+) -> Result<Option<PresetRevision>, String> {
+    preset_revision(&app, &name).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -782,7 +2080,28 @@ pub async fn import_preset_cmd(
     target_dir: String,
 ) -> Result<String, String> {
     let id = asapp
-        .import_preset(path, target_dir.clone())
+        .import_preset(&app, path, target_dir.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(
+        EMIT_PRESET_LIST_CHANGED,
+        PresetListChangedPayload {
+            path: target_dir,
+        },
+    );
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn import_preset_from_text_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    text: String,
+    name: String,
+    target_dir: String,
+) -> Result<String, String> {
+    let id = asapp
+        .import_preset_from_text(&app, text, name, target_dir.clone())
         .await
         .map_err(|e| e.to_string())?;
     let _ = app.emit(
@@ -795,7 +2114,50 @@ pub async fn import_preset_cmd(
 }
 
 #[tauri::command]
-pub async fn start_preset_cmd(asapp: State<'_, ModularAgentApp>, id: String) -> Result<(), String> {
+pub async fn duplicate_preset_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    name: String,
+    target_dir: String,
+    open: bool,
+) -> Result<DuplicatedPreset, String> {
+    let parent_existed =
+        target_dir.is_empty() || folder_path(&app, &target_dir).map(|d| d.exists()).unwrap_or(true);
+    let duplicated = asapp
+        .duplicate_preset(&app, &name, &target_dir, open)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(
+        EMIT_PRESET_LIST_CHANGED,
+        PresetListChangedPayload {
+            path: target_dir.clone(),
+        },
+    );
+    if !parent_existed {
+        let _ = app.emit(
+            EMIT_PRESET_LIST_CHANGED,
+            PresetListChangedPayload {
+                path: parent_preset_path(&target_dir),
+            },
+        );
+    }
+    Ok(duplicated)
+}
+
+/// Start a preset. With `strict`, refuse to start if validation finds errors.
+#[tauri::command]
+pub async fn start_preset_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    id: String,
+    strict: Option<bool>,
+) -> Result<(), String> {
+    if strict.unwrap_or(false) {
+        asapp
+            .check_preset_errors(&app, &id)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
     asapp.start_preset(&id).await.map_err(|e| e.to_string())
 }
 
@@ -813,14 +2175,27 @@ pub async fn close_preset_cmd(
 }
 
 #[tauri::command]
-pub async fn get_dir_entries_cmd(path: String) -> Result<Vec<String>, String> {
-    get_dir_entries(&path).map_err(|e| e.to_string())
+pub async fn get_dir_entries_cmd(app: AppHandle, path: String) -> Result<Vec<String>, String> {
+    get_dir_entries(&app, &path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_dir_entry_infos_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    path: String,
+) -> Result<Vec<DirEntryInfo>, String> {
+    asapp
+        .get_dir_entry_infos(&app, &path)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 pub async fn open_preset_cmd(
+    app: AppHandle,
     asapp: State<'_, ModularAgentApp>,
     name: String,
 ) -> Result<String, String> {
-    asapp.open_preset(name).await.map_err(|e| e.to_string())
+    asapp.open_preset(&app, name).await.map_err(|e| e.to_string())
 }
@@ -0,0 +1,175 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::modular_agent_desktop::app::{ModularAgentApp, PresetConflict};
+use crate::modular_agent_desktop::metadata;
+use crate::modular_agent_desktop::settings::CoreSettings;
+
+const EMIT_PRESET_DIRTY_CHANGED: &str = "ma:preset_dirty_changed";
+const EMIT_PRESET_AUTOSAVE_CONFLICT: &str = "ma:preset_autosave_conflict";
+
+/// How often open presets are checked for unsaved edits.
+const DIRTY_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Serialize)]
+pub struct DirtyPreset {
+    id: String,
+    name: String,
+}
+
+#[derive(Clone, Serialize)]
+struct PresetAutosaveConflictPayload {
+    id: String,
+    name: String,
+    message: String,
+}
+
+#[derive(Clone, Serialize)]
+struct PresetDirtyChangedPayload {
+    id: String,
+    name: String,
+    dirty: bool,
+}
+
+fn autosave_interval(app: &AppHandle) -> Option<Duration> {
+    let core_settings = app.state::<Mutex<CoreSettings>>();
+    let settings = core_settings.lock().unwrap();
+    settings
+        .autosave_interval
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+}
+
+fn emit_dirty_changed(app: &AppHandle, id: &str, name: &str, dirty: bool) {
+    let _ = app.emit(
+        EMIT_PRESET_DIRTY_CHANGED,
+        PresetDirtyChangedPayload {
+            id: id.to_string(),
+            name: name.to_string(),
+            dirty,
+        },
+    );
+}
+
+async fn dirty_presets(asapp: &ModularAgentApp) -> Vec<DirtyPreset> {
+    let mut dirty = Vec::new();
+    for (name, id) in asapp.open_presets() {
+        if asapp.is_dirty(&id).await {
+            dirty.push(DirtyPreset { id, name });
+        }
+    }
+    dirty
+}
+
+/// Start checking open presets for unsaved edits, saving them if autosave is enabled.
+pub fn start(app: AppHandle) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DIRTY_CHECK_INTERVAL);
+        let mut last_autosave = Instant::now();
+        loop {
+            interval.tick().await;
+            let dirty = check(&app).await;
+
+            let Some(autosave_interval) = autosave_interval(&app) else {
+                continue;
+            };
+            if last_autosave.elapsed() < autosave_interval {
+                continue;
+            }
+            last_autosave = Instant::now();
+            if !dirty.is_empty() {
+                save_presets(&app, &dirty).await;
+            }
+        }
+    });
+}
+
+/// Find the open presets with unsaved edits and report changes of their state.
+pub(crate) async fn check(app: &AppHandle) -> Vec<DirtyPreset> {
+    let asapp = app.state::<ModularAgentApp>();
+    asapp.prune_saved_specs();
+    let dirty = dirty_presets(&asapp).await;
+
+    let ids: Vec<(String, String)> = dirty
+        .iter()
+        .map(|p| (p.id.clone(), p.name.clone()))
+        .collect();
+    for (id, name, is_dirty) in asapp.update_reported_dirty(&ids) {
+        if !is_dirty {
+            asapp.clear_autosave_conflict(&id);
+        }
+        emit_dirty_changed(app, &id, &name, is_dirty);
+    }
+
+    dirty
+}
+
+/// Report a preset as clean after it was saved.
+pub(crate) fn mark_saved(app: &AppHandle, id: &str, name: &str) {
+    let asapp = app.state::<ModularAgentApp>();
+    asapp.clear_autosave_conflict(id);
+    if asapp.clear_reported_dirty(id) {
+        emit_dirty_changed(app, id, name, false);
+    }
+}
+
+/// Skip a preset whose file changed elsewhere, leaving its edits in memory.
+fn report_conflict(app: &AppHandle, preset: &DirtyPreset, conflict: &PresetConflict) {
+    log::warn!("Not autosaving preset {}: {}", preset.name, conflict);
+    let asapp = app.state::<ModularAgentApp>();
+    if !asapp.record_autosave_conflict(&preset.id) {
+        return;
+    }
+    let _ = app.emit(
+        EMIT_PRESET_AUTOSAVE_CONFLICT,
+        PresetAutosaveConflictPayload {
+            id: preset.id.clone(),
+            name: preset.name.clone(),
+            message: conflict.to_string(),
+        },
+    );
+}
+
+/// Save the in-memory specs of the given presets to their files.
+/// Presets whose files were changed elsewhere are not overwritten.
+async fn save_presets(app: &AppHandle, presets: &[DirtyPreset]) {
+    let asapp = app.state::<ModularAgentApp>();
+    for preset in presets {
+        // Locked presets keep their unsaved edits in memory only
+        if metadata::is_preset_read_only(app, &preset.name) {
+            continue;
+        }
+        let spec = match asapp.get_open_preset_spec(&preset.name).await {
+            Ok(spec) => spec,
+            Err(e) => {
+                log::warn!("Failed to get preset {} for saving: {}", preset.name, e);
+                continue;
+            }
+        };
+        match asapp.autosave_preset(app, preset.name.clone(), spec) {
+            Ok(()) => log::info!("Saved preset {}", preset.name),
+            Err(e) => match e.downcast_ref::<PresetConflict>() {
+                Some(conflict) => report_conflict(app, preset, conflict),
+                None => log::error!("Failed to save preset {}: {}", preset.name, e),
+            },
+        }
+    }
+}
+
+/// Save every open preset with unsaved edits. Called on exit.
+pub async fn flush(app: &AppHandle) {
+    let asapp = app.state::<ModularAgentApp>();
+    let dirty = dirty_presets(&asapp).await;
+    if !dirty.is_empty() {
+        log::info!("Saving {} preset(s) with unsaved edits", dirty.len());
+        save_presets(app, &dirty).await;
+    }
+}
+
+#[tauri::command]
+pub async fn get_dirty_presets_cmd(app: AppHandle) -> Vec<DirtyPreset> {
+    check(&app).await
+}
@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::task::JoinSet;
+
+use crate::modular_agent_desktop::app::{list_presets_recursive, ModularAgentApp};
+
+#[derive(Clone, Copy)]
+enum BulkAction {
+    Start { strict: bool },
+    Stop,
+}
+
+impl BulkAction {
+    fn verb(&self) -> &'static str {
+        match self {
+            BulkAction::Start { .. } => "start",
+            BulkAction::Stop => "stop",
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct BulkPresetResult {
+    name: String,
+
+    /// Preset ID, if the preset is open.
+    id: Option<String>,
+
+    ok: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Presets to act on: the given names, followed by the presets under `folder`.
+fn select_presets(
+    app: &AppHandle,
+    names: Option<Vec<String>>,
+    folder: Option<String>,
+) -> Result<Vec<String>> {
+    let mut selected = names.unwrap_or_default();
+    if let Some(folder) = folder {
+        selected.extend(list_presets_recursive(app, &folder)?);
+    }
+    let mut seen = HashSet::new();
+    selected.retain(|name| seen.insert(name.clone()));
+    if selected.is_empty() {
+        bail!("No presets selected");
+    }
+    Ok(selected)
+}
+
+/// Start a preset, opening it first if needed. Running presets are left alone.
+async fn start_one(app: &AppHandle, name: &str, strict: bool) -> (Option<String>, Result<()>) {
+    let asapp = app.state::<ModularAgentApp>();
+    let id = match asapp.open_preset(app, name.to_string()).await {
+        Ok(id) => id,
+        Err(e) => return (None, Err(e)),
+    };
+    if asapp.is_running(&id).await {
+        return (Some(id), Ok(()));
+    }
+    if strict {
+        if let Err(e) = asapp.check_preset_errors(app, &id).await {
+            return (Some(id), Err(e));
+        }
+    }
+    let result = asapp.start_preset(&id).await;
+    (Some(id), result)
+}
+
+/// Stop a preset if it is open and running.
+async fn stop_one(app: &AppHandle, name: &str) -> (Option<String>, Result<()>) {
+    let asapp = app.state::<ModularAgentApp>();
+    let Some(id) = asapp.get_preset_id(name) else {
+        return (None, Ok(()));
+    };
+    if !asapp.is_running(&id).await {
+        return (Some(id), Ok(()));
+    }
+    let result = asapp.stop_preset(&id).await;
+    (Some(id), result)
+}
+
+/// Run `action` on all presets concurrently, returning the results in the given order.
+async fn run_bulk(
+    app: &AppHandle,
+    names: Vec<String>,
+    action: BulkAction,
+) -> Vec<BulkPresetResult> {
+    let mut tasks = JoinSet::new();
+    for (i, name) in names.iter().enumerate() {
+        let app = app.clone();
+        let name = name.clone();
+        tasks.spawn(async move {
+            let (id, result) = match action {
+                BulkAction::Start { strict } => start_one(&app, &name, strict).await,
+                BulkAction::Stop => stop_one(&app, &name).await,
+            };
+            (i, id, result)
+        });
+    }
+
+    let mut results: Vec<Option<BulkPresetResult>> = vec![None; names.len()];
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((i, id, result)) = joined else {
+            continue;
+        };
+        if let Err(e) = &result {
+            log::error!("Failed to {} preset {}: {}", action.verb(), names[i], e);
+        }
+        results[i] = Some(BulkPresetResult {
+            name: names[i].clone(),
+            id,
+            ok: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+
+    // Tasks that panicked are reported as failures
+    results
+        .into_iter()
+        .zip(names)
+        .map(|(result, name)| {
+            result.unwrap_or_else(|| BulkPresetResult {
+                name,
+                id: None,
+                ok: false,
+                error: Some("Task failed".to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Start the given presets and/or all presets under `folder`, opening them as needed.
+/// With `strict`, presets with validation errors are not started.
+#[tauri::command]
+pub async fn start_presets_cmd(
+    app: AppHandle,
+    names: Option<Vec<String>>,
+    folder: Option<String>,
+    strict: Option<bool>,
+) -> Result<Vec<BulkPresetResult>, String> {
+    let names = select_presets(&app, names, folder).map_err(|e| e.to_string())?;
+    let action = BulkAction::Start {
+        strict: strict.unwrap_or(false),
+    };
+    Ok(run_bulk(&app, names, action).await)
+}
+
+/// Stop the given presets and/or all presets under `folder`.
+#[tauri::command]
+pub async fn stop_presets_cmd(
+    app: AppHandle,
+    names: Option<Vec<String>>,
+    folder: Option<String>,
+) -> Result<Vec<BulkPresetResult>, String> {
+    let names = select_presets(&app, names, folder).map_err(|e| e.to_string())?;
+    Ok(run_bulk(&app, names, BulkAction::Stop).await)
+}
@@ -0,0 +1,489 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{Read as _, Write as _};
+use std::path::Path;
+
+use anyhow::{bail, Context as _, Result};
+use modular_agent_core::PresetSpec;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_modular_agent::ModularAgentExt;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::modular_agent_desktop::app::{
+    folder_path, is_valid_preset_name, join_preset_path, list_presets_recursive,
+    parent_preset_path, preset_basename, preset_path, preset_path_exists,
+    unique_preset_name_avoiding, ModularAgentApp, PresetListChangedPayload,
+    EMIT_PRESET_LIST_CHANGED,
+};
+use crate::modular_agent_desktop::metadata;
+use crate::modular_agent_desktop::schema;
+use crate::modular_agent_desktop::workspace::{
+    split_workspace, workspace_prefix, WORKSPACE_SEPARATOR,
+};
+
+/// Name of the manifest file at the root of a bundle.
+pub(crate) const BUNDLE_MANIFEST_JSON: &str = "manifest.json";
+
+/// Directory inside a bundle that holds the preset files.
+pub(crate) const BUNDLE_PRESETS_DIR: &str = "presets";
+
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Describes the content of a bundle and what the presets need on the target machine.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub(crate) version: u32,
+
+    /// Preset names relative to the bundle root.
+    pub(crate) presets: Vec<String>,
+
+    /// Agent definitions used by the presets.
+    #[serde(default)]
+    pub(crate) agent_definitions: Vec<String>,
+
+    /// Global config keys of the used agent definitions.
+    /// Only the keys are exported; values such as API keys stay on this machine.
+    #[serde(default)]
+    pub(crate) global_configs: BTreeMap<String, Vec<String>>,
+}
+
+/// A preset to be written into a bundle.
+struct BundlePreset {
+    /// Name relative to the bundle root.
+    name: String,
+    content: String,
+    spec: PresetSpec,
+}
+
+fn read_bundle_preset(app: &AppHandle, name: &str, bundle_name: String) -> Result<BundlePreset> {
+    let path = preset_path(app, name)?;
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read preset file: {}", name))?;
+    let spec = PresetSpec::from_json(&content)
+        .with_context(|| format!("Failed to parse preset: {}", name))?;
+    Ok(BundlePreset {
+        name: bundle_name,
+        content,
+        spec,
+    })
+}
+
+/// Build the manifest for a set of presets.
+fn build_manifest(app: &AppHandle, presets: &[BundlePreset]) -> BundleManifest {
+    let def_names: BTreeSet<String> = presets
+        .iter()
+        .flat_map(|p| p.spec.agents.iter().map(|agent| agent.def_name.clone()))
+        .collect();
+
+    let global_configs_map = app.ma().get_global_configs_map();
+    let mut global_configs = BTreeMap::new();
+    for def_name in &def_names {
+        let Some(configs) = global_configs_map.get(def_name) else {
+            continue;
+        };
+        let keys: Vec<String> = serde_json::to_value(configs)
+            .ok()
+            .and_then(|v| v.as_object().map(|o| o.keys().cloned().collect()))
+            .unwrap_or_default();
+        if !keys.is_empty() {
+            global_configs.insert(def_name.clone(), keys);
+        }
+    }
+
+    BundleManifest {
+        version: BUNDLE_FORMAT_VERSION,
+        presets: presets.iter().map(|p| p.name.clone()).collect(),
+        agent_definitions: def_names.into_iter().collect(),
+        global_configs,
+    }
+}
+
+/// Write presets into a zip bundle at `dest`.
+fn write_bundle(app: &AppHandle, dest: &Path, presets: &[BundlePreset]) -> Result<BundleManifest> {
+    let manifest = build_manifest(app, presets);
+
+    let file = std::fs::File::create(dest)
+        .with_context(|| format!("Failed to create bundle: {:?}", dest))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(BUNDLE_MANIFEST_JSON, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+
+    for preset in presets {
+        zip.start_file(
+            format!("{}/{}.json", BUNDLE_PRESETS_DIR, preset.name),
+            options,
+        )?;
+        zip.write_all(preset.content.as_bytes())?;
+    }
+
+    zip.finish()
+        .with_context(|| format!("Failed to write bundle: {:?}", dest))?;
+
+    Ok(manifest)
+}
+
+/// Export a single preset as a JSON file, or as a bundle if `dest` ends with `.zip`.
+pub(crate) fn export_preset(app: &AppHandle, name: &str, dest: &Path) -> Result<BundleManifest> {
+    if !is_valid_preset_name(app, name) {
+        bail!("Invalid preset name: {}", name);
+    }
+    let basename = preset_basename(name).to_string();
+    let preset = read_bundle_preset(app, name, basename)?;
+
+    if dest.extension().unwrap_or_default() == "zip" {
+        return write_bundle(app, dest, &[preset]);
+    }
+
+    std::fs::write(dest, &preset.content)
+        .with_context(|| format!("Failed to write file: {:?}", dest))?;
+    Ok(build_manifest(app, &[preset]))
+}
+
+/// Export a folder with all its presets as a zip bundle.
+/// The folder itself is the top level of the bundle, so importing it recreates the folder.
+pub(crate) fn export_folder(app: &AppHandle, path: &str, dest: &Path) -> Result<BundleManifest> {
+    if !split_workspace(path).1.is_empty() && !is_valid_preset_name(app, path) {
+        bail!("Invalid folder path: {}", path);
+    }
+    if !folder_path(app, path)?.is_dir() {
+        bail!("Folder not found: {}", path);
+    }
+
+    // Names in the bundle are relative to the parent of the exported folder
+    let parent_len = join_preset_path(&parent_preset_path(path), "").len();
+
+    let mut presets = Vec::new();
+    for name in list_presets_recursive(app, path)? {
+        let bundle_name = name[parent_len..].to_string();
+        presets.push(read_bundle_preset(app, &name, bundle_name)?);
+    }
+
+    write_bundle(app, dest, &presets)
+}
+
+// Import
+
+/// What to do when an imported preset has the same name as an existing one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    Skip,
+    Overwrite,
+    #[default]
+    Rename,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Create,
+    Overwrite,
+    Rename,
+    Skip,
+}
+
+/// One entry of an import plan: where a source preset goes and what happens to it.
+#[derive(Clone, Serialize)]
+pub struct ImportPlanEntry {
+    /// Preset name relative to the imported directory or bundle.
+    source: String,
+
+    /// Preset name in the library.
+    name: String,
+
+    action: ImportAction,
+
+    /// Reason the preset is skipped, if it cannot be imported.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A preset read from an import source, before it is validated.
+struct ImportSource {
+    name: String,
+    content: String,
+}
+
+/// Read presets from a directory. The directory itself becomes the top-level folder.
+fn read_dir_sources(dir: &Path) -> Result<Vec<ImportSource>> {
+    let dir_name = dir
+        .file_name()
+        .context("Failed to get directory name")?
+        .to_string_lossy()
+        .to_string();
+    let mut sources = Vec::new();
+    collect_dir_sources(dir, &dir_name, &mut sources)?;
+    Ok(sources)
+}
+
+fn collect_dir_sources(dir: &Path, prefix: &str, sources: &mut Vec<ImportSource>) -> Result<()> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read directory: {:?}", dir))?;
+    for entry in entries {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if file_name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_dir_sources(&path, &format!("{}/{}", prefix, file_name), sources)?;
+        } else if path.is_file() && path.extension().unwrap_or_default() == "json" {
+            let stem = path
+                .file_stem()
+                .context("Failed to get file stem")?
+                .to_string_lossy()
+                .to_string();
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read file: {:?}", path))?;
+            sources.push(ImportSource {
+                name: format!("{}/{}", prefix, stem),
+                content,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Read presets from a zip bundle.
+/// Files under `presets/` are used if present, otherwise every JSON file except the manifest.
+fn read_zip_sources(path: &Path) -> Result<Vec<ImportSource>> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open bundle: {:?}", path))?;
+    let mut archive =
+        ZipArchive::new(file).with_context(|| format!("Failed to read bundle: {:?}", path))?;
+
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Reject entries escaping the bundle root
+        let Some(entry_path) = entry.enclosed_name() else {
+            log::warn!("Skipping unsafe bundle entry: {}", entry.name());
+            continue;
+        };
+        let entry_name = entry_path.to_string_lossy().replace('\\', "/");
+        if !entry_name.ends_with(".json") || entry_name == BUNDLE_MANIFEST_JSON {
+            continue;
+        }
+        if entry_name.split('/').any(|c| c.starts_with('.')) {
+            continue;
+        }
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .with_context(|| format!("Failed to read bundle entry: {}", entry_name))?;
+        files.push((entry_name, content));
+    }
+
+    let presets_prefix = format!("{}/", BUNDLE_PRESETS_DIR);
+    let has_presets_dir = files.iter().any(|(n, _)| n.starts_with(&presets_prefix));
+    let sources = files
+        .into_iter()
+        .filter_map(|(entry_name, content)| {
+            let rel = if has_presets_dir {
+                entry_name.strip_prefix(&presets_prefix)?
+            } else {
+                entry_name.as_str()
+            };
+            Some(ImportSource {
+                name: rel.trim_end_matches(".json").to_string(),
+                content,
+            })
+        })
+        .collect();
+    Ok(sources)
+}
+
+fn read_import_sources(path: &Path) -> Result<Vec<ImportSource>> {
+    if path.is_dir() {
+        return read_dir_sources(path);
+    }
+    if !path.is_file() {
+        bail!("File not found: {:?}", path);
+    }
+    if path.extension().unwrap_or_default() == "zip" {
+        return read_zip_sources(path);
+    }
+    let name = path
+        .file_stem()
+        .context("Failed to get file stem")?
+        .to_string_lossy()
+        .to_string();
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read file: {:?}", path))?;
+    Ok(vec![ImportSource { name, content }])
+}
+
+/// Parse an imported preset, upgrading it from older format versions.
+fn parse_source(content: &str) -> Result<PresetSpec> {
+    match schema::migrate_json(content)? {
+        Some((json, _)) => Ok(PresetSpec::from_json(&json)?),
+        None => Ok(PresetSpec::from_json(content)?),
+    }
+}
+
+/// Decide what happens to each source preset.
+fn plan_import(
+    app: &AppHandle,
+    asapp: &ModularAgentApp,
+    sources: &[ImportSource],
+    target_dir: &str,
+    policy: ConflictPolicy,
+) -> Vec<ImportPlanEntry> {
+    let mut taken = HashSet::new();
+    let mut plan = Vec::new();
+
+    for source in sources {
+        let base_name = join_preset_path(target_dir, &source.name);
+        let mut entry = ImportPlanEntry {
+            source: source.name.clone(),
+            name: base_name.clone(),
+            action: ImportAction::Create,
+            error: None,
+        };
+
+        // Source names are relative and must not select a workspace
+        if source.name.contains(WORKSPACE_SEPARATOR) || !is_valid_preset_name(app, &base_name) {
+            entry.action = ImportAction::Skip;
+            entry.error = Some(format!("Invalid preset name: {}", base_name));
+        } else if let Err(e) = parse_source(&source.content) {
+            entry.action = ImportAction::Skip;
+            entry.error = Some(format!("Failed to parse preset: {}", e));
+        } else if preset_path_exists(app, &base_name) || taken.contains(&base_name) {
+            match policy {
+                ConflictPolicy::Skip => {
+                    entry.action = ImportAction::Skip;
+                }
+                ConflictPolicy::Overwrite => {
+                    if taken.contains(&base_name) {
+                        entry.action = ImportAction::Skip;
+                        entry.error =
+                            Some("Another preset in this import has the same name.".to_string());
+                    } else if asapp.get_preset_id(&base_name).is_some() {
+                        entry.action = ImportAction::Skip;
+                        entry.error = Some("The preset is open. Close it first.".to_string());
+                    } else {
+                        entry.action = ImportAction::Overwrite;
+                    }
+                }
+                ConflictPolicy::Rename => {
+                    entry.name =
+                        unique_preset_name_avoiding(app, &base_name, |n| taken.contains(n));
+                    entry.action = ImportAction::Rename;
+                }
+            }
+        }
+
+        // Never write into read-only folders or over locked presets
+        if entry.action != ImportAction::Skip {
+            if let Err(e) = metadata::ensure_preset_writable(app, &entry.name) {
+                entry.action = ImportAction::Skip;
+                entry.error = Some(e.to_string());
+            }
+        }
+
+        if entry.action != ImportAction::Skip {
+            taken.insert(entry.name.clone());
+        }
+        plan.push(entry);
+    }
+
+    plan
+}
+
+/// Import presets from a directory, a zip bundle or a single JSON file into `target_dir`,
+/// recreating the relative folder structure. With `dry_run`, only the plan is returned.
+pub(crate) fn import_presets(
+    app: &AppHandle,
+    asapp: &ModularAgentApp,
+    path: &Path,
+    target_dir: &str,
+    policy: ConflictPolicy,
+    dry_run: bool,
+) -> Result<Vec<ImportPlanEntry>> {
+    if !target_dir.is_empty() && !is_valid_preset_name(app, target_dir) {
+        bail!("Invalid target directory: {}", target_dir);
+    }
+
+    let sources = read_import_sources(path)?;
+    let mut plan = plan_import(app, asapp, &sources, target_dir, policy);
+    if dry_run {
+        return Ok(plan);
+    }
+
+    // A failing preset is reported in its entry and does not stop the others
+    let mut changed_dirs = BTreeSet::new();
+    for (source, entry) in sources.iter().zip(plan.iter_mut()) {
+        if entry.action == ImportAction::Skip {
+            continue;
+        }
+        let saved = parse_source(&source.content)
+            .with_context(|| format!("Failed to parse preset: {}", source.name))
+            .and_then(|spec| asapp.save_preset(app, entry.name.clone(), spec));
+        if let Err(e) = saved {
+            log::error!("Failed to import preset {}: {}", source.name, e);
+            entry.action = ImportAction::Skip;
+            entry.error = Some(e.to_string());
+            continue;
+        }
+
+        // Every ancestor may have been created by this import
+        let root = workspace_prefix(split_workspace(&entry.name).0);
+        let mut parent = entry.name.clone();
+        while parent != root {
+            parent = parent_preset_path(&parent);
+            changed_dirs.insert(parent.clone());
+        }
+    }
+
+    for path in changed_dirs {
+        let _ = app.emit(EMIT_PRESET_LIST_CHANGED, PresetListChangedPayload { path });
+    }
+
+    Ok(plan)
+}
+
+#[tauri::command]
+pub fn export_preset_cmd(
+    app: AppHandle,
+    name: String,
+    dest: String,
+) -> Result<BundleManifest, String> {
+    export_preset(&app, &name, Path::new(&dest)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn export_folder_cmd(
+    app: AppHandle,
+    path: String,
+    dest: String,
+) -> Result<BundleManifest, String> {
+    export_folder(&app, &path, Path::new(&dest)).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn import_presets_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    path: String,
+    target_dir: String,
+    policy: Option<ConflictPolicy>,
+    dry_run: bool,
+) -> Result<Vec<ImportPlanEntry>, String> {
+    import_presets(
+        &app,
+        &asapp,
+        Path::new(&path),
+        &target_dir,
+        policy.unwrap_or_default(),
+        dry_run,
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{bail, Result};
+use modular_agent_core::PresetSpec;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, State};
+
+use crate::modular_agent_desktop::app::{is_valid_preset_name, read_preset_file, ModularAgentApp};
+use crate::modular_agent_desktop::versions;
+
+/// Agent keys that only affect how the preset is drawn.
+const LAYOUT_KEYS: &[&str] = &["x", "y", "width", "height"];
+
+/// Top-level keys that only affect how the preset is drawn.
+const PRESET_LAYOUT_KEYS: &[&str] = &["viewport"];
+
+/// Top-level keys that are compared separately, or not at all.
+const PRESET_SKIPPED_KEYS: &[&str] = &["agents", "connections", "format_version"];
+
+/// One side of a diff.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum DiffSource {
+    /// Saved preset file.
+    File { name: String },
+
+    /// Open preset in memory, including unsaved edits.
+    Open { name: String },
+
+    /// Saved version of a preset.
+    Version { name: String, version: String },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AgentRef {
+    id: String,
+    def_name: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValueChange {
+    key: String,
+
+    /// `None` if the key was added.
+    before: Option<Value>,
+
+    /// `None` if the key was removed.
+    after: Option<Value>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AgentChange {
+    id: String,
+    def_name: String,
+
+    /// Changed config values.
+    configs: Vec<ValueChange>,
+
+    /// Other changed properties, e.g. the definition or the ports.
+    properties: Vec<ValueChange>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LayoutChange {
+    /// Agent ID, or `None` for the preset itself (viewport).
+    agent_id: Option<String>,
+    changes: Vec<ValueChange>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ConnectionRef {
+    source: String,
+    source_handle: String,
+    target: String,
+    target_handle: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PresetDiff {
+    agents_added: Vec<AgentRef>,
+    agents_removed: Vec<AgentRef>,
+    agents_changed: Vec<AgentChange>,
+    connections_added: Vec<ConnectionRef>,
+    connections_removed: Vec<ConnectionRef>,
+
+    /// Other top-level properties of the preset.
+    properties: Vec<ValueChange>,
+
+    /// Moved or resized agents and viewport changes, kept apart from the rest.
+    layout: Vec<LayoutChange>,
+
+    /// True if only the layout changed.
+    layout_only: bool,
+
+    /// True if nothing changed at all.
+    identical: bool,
+}
+
+/// Changes of the values in `before` and `after`, limited to the keys accepted by `filter`.
+fn diff_maps(
+    before: &Map<String, Value>,
+    after: &Map<String, Value>,
+    filter: impl Fn(&str) -> bool,
+) -> Vec<ValueChange> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| filter(key))
+        .filter_map(|key| {
+            let (b, a) = (before.get(key), after.get(key));
+            (b != a).then(|| ValueChange {
+                key: key.clone(),
+                before: b.cloned(),
+                after: a.cloned(),
+            })
+        })
+        .collect()
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn object<'a>(value: Option<&'a Value>, empty: &'a Map<String, Value>) -> &'a Map<String, Value> {
+    value.and_then(Value::as_object).unwrap_or(empty)
+}
+
+/// Agents by ID, in ID order.
+fn agents_by_id(preset: &Value) -> BTreeMap<String, &Value> {
+    let agents = preset.get("agents").and_then(Value::as_array);
+    agents
+        .into_iter()
+        .flatten()
+        .map(|a| (str_field(a, "id").to_string(), a))
+        .collect()
+}
+
+fn connections(preset: &Value) -> BTreeSet<ConnectionRef> {
+    let connections = preset.get("connections").and_then(Value::as_array);
+    connections
+        .into_iter()
+        .flatten()
+        .map(|c| ConnectionRef {
+            source: str_field(c, "source").to_string(),
+            source_handle: str_field(c, "source_handle").to_string(),
+            target: str_field(c, "target").to_string(),
+            target_handle: str_field(c, "target_handle").to_string(),
+        })
+        .collect()
+}
+
+fn agent_ref(id: &str, agent: &Value) -> AgentRef {
+    AgentRef {
+        id: id.to_string(),
+        def_name: str_field(agent, "def_name").to_string(),
+    }
+}
+
+/// Compare two presets. Agents are matched by ID, connections by their endpoints.
+pub(crate) fn diff_specs(before: &PresetSpec, after: &PresetSpec) -> Result<PresetDiff> {
+    let before = serde_json::to_value(before)?;
+    let after = serde_json::to_value(after)?;
+    let empty = Map::new();
+    let mut diff = PresetDiff::default();
+
+    let before_agents = agents_by_id(&before);
+    let after_agents = agents_by_id(&after);
+    for (id, agent) in &before_agents {
+        if !after_agents.contains_key(id) {
+            diff.agents_removed.push(agent_ref(id, agent));
+        }
+    }
+    for (id, agent) in &after_agents {
+        let Some(old) = before_agents.get(id) else {
+            diff.agents_added.push(agent_ref(id, agent));
+            continue;
+        };
+
+        let (old_fields, new_fields) = (object(Some(old), &empty), object(Some(agent), &empty));
+        let configs = diff_maps(
+            object(old.get("configs"), &empty),
+            object(agent.get("configs"), &empty),
+            |_| true,
+        );
+        let properties = diff_maps(old_fields, new_fields, |key| {
+            key != "configs" && !LAYOUT_KEYS.contains(&key)
+        });
+        let layout = diff_maps(old_fields, new_fields, |key| LAYOUT_KEYS.contains(&key));
+
+        if !configs.is_empty() || !properties.is_empty() {
+            diff.agents_changed.push(AgentChange {
+                id: id.clone(),
+                def_name: str_field(agent, "def_name").to_string(),
+                configs,
+                properties,
+            });
+        }
+        if !layout.is_empty() {
+            diff.layout.push(LayoutChange {
+                agent_id: Some(id.clone()),
+                changes: layout,
+            });
+        }
+    }
+
+    let before_connections = connections(&before);
+    let after_connections = connections(&after);
+    diff.connections_removed = before_connections
+        .difference(&after_connections)
+        .cloned()
+        .collect();
+    diff.connections_added = after_connections
+        .difference(&before_connections)
+        .cloned()
+        .collect();
+
+    let (before_fields, after_fields) =
+        (object(Some(&before), &empty), object(Some(&after), &empty));
+    diff.properties = diff_maps(before_fields, after_fields, |key| {
+        !PRESET_SKIPPED_KEYS.contains(&key) && !PRESET_LAYOUT_KEYS.contains(&key)
+    });
+    let viewport = diff_maps(before_fields, after_fields, |key| {
+        PRESET_LAYOUT_KEYS.contains(&key)
+    });
+    if !viewport.is_empty() {
+        diff.layout.insert(
+            0,
+            LayoutChange {
+                agent_id: None,
+                changes: viewport,
+            },
+        );
+    }
+
+    let structural = !diff.agents_added.is_empty()
+        || !diff.agents_removed.is_empty()
+        || !diff.agents_changed.is_empty()
+        || !diff.connections_added.is_empty()
+        || !diff.connections_removed.is_empty()
+        || !diff.properties.is_empty();
+    diff.layout_only = !structural && !diff.layout.is_empty();
+    diff.identical = !structural && diff.layout.is_empty();
+    Ok(diff)
+}
+
+async fn load_source(
+    app: &AppHandle,
+    asapp: &ModularAgentApp,
+    source: &DiffSource,
+) -> Result<PresetSpec> {
+    match source {
+        DiffSource::File { name } => {
+            if !is_valid_preset_name(app, name) {
+                bail!("Invalid preset name: {}", name);
+            }
+            read_preset_file(app, name)
+        }
+        DiffSource::Open { name } => asapp.get_open_preset_spec(name).await,
+        DiffSource::Version { name, version } => versions::read_version(app, name, version),
+    }
+}
+
+/// Compare two presets, e.g. two saved presets, a preset and one of its versions,
+/// or an open preset against its file.
+#[tauri::command]
+pub async fn diff_presets_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    before: DiffSource,
+    after: DiffSource,
+) -> Result<PresetDiff, String> {
+    let before = load_source(&app, &asapp, &before)
+        .await
+        .map_err(|e| e.to_string())?;
+    let after = load_source(&app, &asapp, &after)
+        .await
+        .map_err(|e| e.to_string())?;
+    diff_specs(&before, &after).map_err(|e| e.to_string())
+}
@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context as _, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::modular_agent_desktop::app::{copy_path, data_dir_override, move_path};
+
+/// Data directory in the home directory, used unless another layout is selected.
+static MODULAR_AGENT_PATH: &str = ".modular_agent";
+
+/// Directory name under the XDG base directories.
+static XDG_APP_NAME: &str = "modular-agent";
+
+/// Marker file next to the executable that enables the portable layout.
+const PORTABLE_MARKER: &str = "portable";
+
+/// Directory next to the executable holding everything in the portable layout.
+static PORTABLE_DATA_PATH: &str = "data";
+
+/// Command-line flag and environment variable that select the XDG layout on Linux.
+const XDG_ARG: &str = "--xdg";
+const XDG_ENV: &str = "MODULAR_AGENT_XDG";
+
+const SETTINGS_JSON: &str = "settings.json";
+
+/// Layout selected in `init`.
+static LAYOUT: OnceLock<DataLayout> = OnceLock::new();
+
+/// Where the app keeps its settings, presets and logs.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataLayout {
+    /// Presets in `~/.modular_agent`, settings and logs in the Tauri app directories.
+    Home,
+
+    /// Settings in `$XDG_CONFIG_HOME`, presets in `$XDG_DATA_HOME` (Linux only).
+    Xdg,
+
+    /// Everything next to the executable.
+    Portable,
+}
+
+/// Detect the layout. Must be called before the Tauri app is built.
+pub fn init() -> DataLayout {
+    layout()
+}
+
+pub(crate) fn layout() -> DataLayout {
+    *LAYOUT.get_or_init(detect)
+}
+
+fn portable_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let exe_dir = exe.parent()?;
+    exe_dir
+        .join(PORTABLE_MARKER)
+        .exists()
+        .then(|| exe_dir.join(PORTABLE_DATA_PATH))
+}
+
+fn xdg_data_dir() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join(XDG_APP_NAME))
+}
+
+fn xdg_config_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join(XDG_APP_NAME))
+}
+
+fn detect() -> DataLayout {
+    if portable_dir().is_some() {
+        return DataLayout::Portable;
+    }
+    if cfg!(target_os = "linux") {
+        let requested = std::env::args().skip(1).any(|a| a == XDG_ARG)
+            || std::env::var(XDG_ENV).is_ok_and(|v| !v.is_empty() && v != "0");
+        // Once migrated, keep using the XDG directories without the flag
+        let migrated = xdg_data_dir().is_some_and(|d| d.exists());
+        if requested || migrated {
+            return DataLayout::Xdg;
+        }
+    }
+    DataLayout::Home
+}
+
+/// `~/.modular_agent`, the data directory of the home layout.
+fn home_data_dir() -> Result<PathBuf> {
+    let home_dir = dirs::home_dir().with_context(|| "Failed to get home directory")?;
+    Ok(home_dir.join(MODULAR_AGENT_PATH))
+}
+
+/// Default data directory (presets, versions, trash and `mcp.json`) of the current layout.
+pub(crate) fn default_data_dir() -> Result<PathBuf> {
+    match layout() {
+        DataLayout::Home => home_data_dir(),
+        DataLayout::Xdg => xdg_data_dir().context("Failed to get XDG data directory"),
+        DataLayout::Portable => portable_dir().context("Failed to get portable directory"),
+    }
+}
+
+/// Path passed to `tauri-plugin-store` for the settings.
+/// Relative paths are resolved against the Tauri app data directory.
+pub(crate) fn settings_store_path() -> PathBuf {
+    let dir = match layout() {
+        DataLayout::Home => None,
+        DataLayout::Xdg => xdg_config_dir(),
+        DataLayout::Portable => portable_dir(),
+    };
+    match dir {
+        Some(dir) => dir.join(SETTINGS_JSON),
+        None => PathBuf::from(SETTINGS_JSON),
+    }
+}
+
+/// Log directory, if it differs from the Tauri app log directory.
+pub fn log_dir() -> Option<PathBuf> {
+    match layout() {
+        DataLayout::Portable => portable_dir().map(|d| d.join("logs")),
+        _ => None,
+    }
+}
+
+/// `data_dir` in a settings file, if set.
+fn settings_data_dir(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let settings: serde_json::Value = serde_json::from_str(&content).ok()?;
+    settings
+        .get("core")?
+        .get("data_dir")?
+        .as_str()
+        .map(|s| s.to_string())
+}
+
+/// Move the data of the home layout into the current layout, once.
+/// Nothing is moved if the target already exists, or if the data directory is overridden.
+/// The portable layout copies instead, so the installed app keeps its data.
+pub fn migrate(app: &AppHandle) -> Result<()> {
+    let layout = layout();
+    if layout == DataLayout::Home {
+        return Ok(());
+    }
+
+    let old_settings = app
+        .path()
+        .app_data_dir()
+        .context("Failed to get app data directory")?
+        .join(SETTINGS_JSON);
+    let new_settings = settings_store_path();
+
+    let settings_override = [&new_settings, &old_settings]
+        .into_iter()
+        .filter(|p| p.is_absolute())
+        .find_map(|p| settings_data_dir(p));
+    if data_dir_override(settings_override.as_deref()).is_some() {
+        log::info!("Data directory is overridden, skipping migration");
+        return Ok(());
+    }
+
+    let transfer = |from: &Path, to: &Path| match layout {
+        DataLayout::Portable => copy_path(from, to),
+        _ => move_path(from, to),
+    };
+
+    let old_data_dir = home_data_dir()?;
+    let new_data_dir = default_data_dir()?;
+    if old_data_dir.is_dir() && !new_data_dir.exists() {
+        log::info!(
+            "Migrating data to the {:?} layout: {:?} -> {:?}",
+            layout,
+            old_data_dir,
+            new_data_dir
+        );
+        if let Some(parent) = new_data_dir.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        transfer(&old_data_dir, &new_data_dir)
+            .with_context(|| format!("Failed to migrate data directory: {:?}", old_data_dir))?;
+    }
+
+    if new_settings.is_absolute() && old_settings.is_file() && !new_settings.exists() {
+        log::info!(
+            "Migrating settings: {:?} -> {:?}",
+            old_settings,
+            new_settings
+        );
+        if let Some(parent) = new_settings.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        transfer(&old_settings, &new_settings)
+            .with_context(|| format!("Failed to migrate settings: {:?}", old_settings))?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_data_layout_cmd() -> DataLayout {
+    layout()
+}
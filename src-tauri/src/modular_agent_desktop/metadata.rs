@@ -0,0 +1,463 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context as _, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::modular_agent_desktop::app::{
+    folder_path, is_valid_preset_name, join_preset_path, list_presets_recursive,
+    parent_preset_path, preset_path, PresetListChangedPayload, EMIT_PRESET_LIST_CHANGED,
+};
+use crate::modular_agent_desktop::workspace::{split_workspace, workspace_prefix, workspace_roots};
+
+/// Metadata of a folder, stored inside it.
+const FOLDER_METADATA_FILE: &str = ".folder.meta";
+
+/// Metadata of a preset, stored next to it, e.g. "MyPreset.json.meta".
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PresetMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Emoji or icon name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+
+    /// CSS color, e.g. "#ff8800".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Milliseconds since the Unix epoch, set when the preset is first saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+
+    /// Milliseconds since the Unix epoch, set on every save.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<u64>,
+
+    /// Locked presets cannot be saved, moved, renamed or deleted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+}
+
+/// A change to the user-editable part of `PresetMetadata`.
+/// Omitted fields are kept as they are, and `null` clears a field.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PresetMetadataEdit {
+    #[serde(default, deserialize_with = "present")]
+    description: Option<Option<String>>,
+
+    #[serde(default, deserialize_with = "present")]
+    tags: Option<Option<Vec<String>>>,
+
+    #[serde(default, deserialize_with = "present")]
+    icon: Option<Option<String>>,
+
+    #[serde(default, deserialize_with = "present")]
+    color: Option<Option<String>>,
+
+    #[serde(default, deserialize_with = "present")]
+    author: Option<Option<String>>,
+}
+
+/// Deserialize a field that is present, so `null` becomes `Some(None)` rather than `None`.
+fn present<'de, D, T>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Clone, Serialize)]
+pub struct TaggedPreset {
+    name: String,
+    metadata: PresetMetadata,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TagCount {
+    tag: String,
+    count: usize,
+}
+
+/// Metadata of a folder.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FolderMetadata {
+    /// Nothing in a read-only folder can be changed, including its subfolders.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+}
+
+/// Sidecar of a preset file, e.g. "MyPreset.json" -> "MyPreset.json.meta".
+pub(crate) fn preset_metadata_file(preset_file: &Path) -> PathBuf {
+    preset_file.with_extension("json.meta")
+}
+
+fn preset_metadata_path(app: &AppHandle, name: &str) -> Result<PathBuf> {
+    Ok(preset_metadata_file(&preset_path(app, name)?))
+}
+
+fn now_millis() -> Option<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as u64)
+}
+
+/// Trim a text field, dropping it if empty.
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Trim tags and drop empty and duplicate ones, keeping their order.
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+fn folder_metadata_path(app: &AppHandle, path: &str) -> Result<PathBuf> {
+    Ok(folder_path(app, path)?.join(FOLDER_METADATA_FILE))
+}
+
+/// Read a metadata file. Missing or broken files read as the default.
+fn read_metadata<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return T::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("Ignoring broken metadata file {:?}: {}", path, e);
+        T::default()
+    })
+}
+
+/// Write a metadata file, or remove it if everything is at the default.
+fn write_metadata<T: Serialize + Default + PartialEq>(path: &Path, metadata: &T) -> Result<()> {
+    if *metadata == T::default() {
+        if path.exists() {
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove metadata file: {:?}", path))?;
+        }
+        return Ok(());
+    }
+    let json = serde_json::to_string_pretty(metadata)?;
+    std::fs::write(path, json).with_context(|| format!("Failed to write metadata file: {:?}", path))
+}
+
+pub(crate) fn read_preset_metadata(app: &AppHandle, name: &str) -> PresetMetadata {
+    match preset_metadata_path(app, name) {
+        Ok(path) => read_metadata(&path),
+        Err(_) => PresetMetadata::default(),
+    }
+}
+
+/// Metadata of a preset, or `None` if it has none.
+pub(crate) fn preset_metadata(app: &AppHandle, name: &str) -> Option<PresetMetadata> {
+    let metadata = read_preset_metadata(app, name);
+    (metadata != PresetMetadata::default()).then_some(metadata)
+}
+
+/// Update the timestamps of a preset after it was saved.
+pub(crate) fn touch_preset(app: &AppHandle, name: &str, is_new: bool) -> Result<()> {
+    let mut metadata = read_preset_metadata(app, name);
+    let now = now_millis();
+    if is_new || metadata.created.is_none() {
+        metadata.created = now;
+    }
+    metadata.updated = now;
+    write_metadata(&preset_metadata_path(app, name)?, &metadata)
+}
+
+/// Give a copied preset the description, tags and looks of the original.
+pub(crate) fn copy_preset_metadata(app: &AppHandle, from: &str, to: &str) -> Result<()> {
+    let source = read_preset_metadata(app, from);
+    let mut metadata = read_preset_metadata(app, to);
+    metadata.description = source.description;
+    metadata.tags = source.tags;
+    metadata.icon = source.icon;
+    metadata.color = source.color;
+    metadata.author = source.author;
+    write_metadata(&preset_metadata_path(app, to)?, &metadata)
+}
+
+pub(crate) fn read_folder_metadata(app: &AppHandle, path: &str) -> FolderMetadata {
+    match folder_metadata_path(app, path) {
+        Ok(path) => read_metadata(&path),
+        Err(_) => FolderMetadata::default(),
+    }
+}
+
+/// The folder marked read-only that contains `path`, or `path` itself if it is one.
+fn read_only_folder(app: &AppHandle, path: &str) -> Option<String> {
+    let mut path = path.to_string();
+    while !split_workspace(&path).1.is_empty() {
+        if read_folder_metadata(app, &path).read_only {
+            return Some(path);
+        }
+        path = parent_preset_path(&path);
+    }
+    None
+}
+
+/// Fail if the folder `path` is read-only, e.g. before creating or removing entries in it.
+pub(crate) fn ensure_folder_writable(app: &AppHandle, path: &str) -> Result<()> {
+    if let Some(folder) = read_only_folder(app, path) {
+        bail!("Folder is read-only: {}", folder);
+    }
+    Ok(())
+}
+
+/// Fail if a preset is locked or in a read-only folder.
+pub(crate) fn ensure_preset_writable(app: &AppHandle, name: &str) -> Result<()> {
+    if read_preset_metadata(app, name).locked {
+        bail!("Preset is locked: {}. Unlock it first.", name);
+    }
+    ensure_folder_writable(app, &parent_preset_path(name))
+}
+
+/// A read-only folder below `path`, searched depth-first.
+fn read_only_subfolder(app: &AppHandle, path: &str) -> Result<Option<String>> {
+    let dir = folder_path(app, path)?;
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+    let dir_entries =
+        std::fs::read_dir(&dir).with_context(|| format!("Failed to read directory: {:?}", dir))?;
+    for entry in dir_entries {
+        let entry_path = entry?.path();
+        let Some(file_name) = entry_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+        if file_name.starts_with('.') || !entry_path.is_dir() {
+            continue;
+        }
+        let sub_path = join_preset_path(path, &file_name);
+        if read_folder_metadata(app, &sub_path).read_only {
+            return Ok(Some(sub_path));
+        }
+        if let Some(folder) = read_only_subfolder(app, &sub_path)? {
+            return Ok(Some(folder));
+        }
+    }
+    Ok(None)
+}
+
+/// Fail if a folder is read-only or contains a read-only folder or a locked preset,
+/// e.g. before moving, renaming or deleting the whole folder.
+pub(crate) fn ensure_folder_contents_writable(app: &AppHandle, path: &str) -> Result<()> {
+    ensure_folder_writable(app, path)?;
+    if let Some(folder) = read_only_subfolder(app, path)? {
+        bail!("Folder contains a read-only folder: {}", folder);
+    }
+    for name in list_presets_recursive(app, path)? {
+        if read_preset_metadata(app, &name).locked {
+            bail!(
+                "Folder contains a locked preset: {}. Unlock it first.",
+                name
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Whether a preset can be changed, considering its lock and its folders.
+pub(crate) fn is_preset_read_only(app: &AppHandle, name: &str) -> bool {
+    ensure_preset_writable(app, name).is_err()
+}
+
+/// Whether a folder can be changed, considering its own flag and its ancestors.
+pub(crate) fn is_folder_read_only(app: &AppHandle, path: &str) -> bool {
+    read_only_folder(app, path).is_some()
+}
+
+pub(crate) fn set_preset_locked(app: &AppHandle, name: &str, locked: bool) -> Result<()> {
+    if !is_valid_preset_name(app, name) {
+        bail!("Invalid preset name: {}", name);
+    }
+    if !preset_path(app, name)?.is_file() {
+        bail!("Preset not found: {}", name);
+    }
+    let mut metadata = read_preset_metadata(app, name);
+    metadata.locked = locked;
+    write_metadata(&preset_metadata_path(app, name)?, &metadata)?;
+    let _ = app.emit(
+        EMIT_PRESET_LIST_CHANGED,
+        PresetListChangedPayload {
+            path: parent_preset_path(name),
+        },
+    );
+    Ok(())
+}
+
+pub(crate) fn set_preset_metadata(
+    app: &AppHandle,
+    name: &str,
+    edit: PresetMetadataEdit,
+) -> Result<PresetMetadata> {
+    if !is_valid_preset_name(app, name) {
+        bail!("Invalid preset name: {}", name);
+    }
+    if !preset_path(app, name)?.is_file() {
+        bail!("Preset not found: {}", name);
+    }
+    let mut metadata = read_preset_metadata(app, name);
+    if let Some(description) = edit.description {
+        metadata.description = non_empty(description);
+    }
+    if let Some(tags) = edit.tags {
+        metadata.tags = normalize_tags(tags.unwrap_or_default());
+    }
+    if let Some(icon) = edit.icon {
+        metadata.icon = non_empty(icon);
+    }
+    if let Some(color) = edit.color {
+        metadata.color = non_empty(color);
+    }
+    if let Some(author) = edit.author {
+        metadata.author = non_empty(author);
+    }
+    write_metadata(&preset_metadata_path(app, name)?, &metadata)?;
+    let _ = app.emit(
+        EMIT_PRESET_LIST_CHANGED,
+        PresetListChangedPayload {
+            path: parent_preset_path(name),
+        },
+    );
+    Ok(metadata)
+}
+
+/// Names of the presets under `path`, or in all workspaces if `None`.
+fn presets_under(app: &AppHandle, path: Option<&str>) -> Result<Vec<String>> {
+    match path {
+        Some(path) => list_presets_recursive(app, path),
+        None => {
+            let mut names = Vec::new();
+            for (workspace, _) in workspace_roots(app)? {
+                names.extend(list_presets_recursive(app, &workspace_prefix(&workspace))?);
+            }
+            Ok(names)
+        }
+    }
+}
+
+/// Presets having all of `tags` (case-insensitive), sorted by name.
+pub(crate) fn find_presets_by_tags(
+    app: &AppHandle,
+    tags: &[String],
+    path: Option<&str>,
+) -> Result<Vec<TaggedPreset>> {
+    let tags = normalize_tags(tags.to_vec());
+    let mut presets = Vec::new();
+    for name in presets_under(app, path)? {
+        let metadata = read_preset_metadata(app, &name);
+        let has_all = tags
+            .iter()
+            .all(|tag| metadata.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+        if has_all && !metadata.tags.is_empty() {
+            presets.push(TaggedPreset { name, metadata });
+        }
+    }
+    Ok(presets)
+}
+
+/// All tags in use with the number of presets having each, sorted by tag.
+/// Tags differing only in case are counted together under the first spelling found.
+pub(crate) fn list_tags(app: &AppHandle, path: Option<&str>) -> Result<Vec<TagCount>> {
+    let mut counts: BTreeMap<String, (String, usize)> = BTreeMap::new();
+    for name in presets_under(app, path)? {
+        for tag in read_preset_metadata(app, &name).tags {
+            let entry = counts
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| (tag.clone(), 0));
+            entry.1 += 1;
+        }
+    }
+    Ok(counts
+        .into_values()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect())
+}
+
+pub(crate) fn set_folder_read_only(app: &AppHandle, path: &str, read_only: bool) -> Result<()> {
+    if split_workspace(path).1.is_empty() || !is_valid_preset_name(app, path) {
+        bail!("Invalid folder path: {}", path);
+    }
+    if !folder_path(app, path)?.is_dir() {
+        bail!("Folder not found: {}", path);
+    }
+    let mut metadata = read_folder_metadata(app, path);
+    metadata.read_only = read_only;
+    write_metadata(&folder_metadata_path(app, path)?, &metadata)?;
+    let _ = app.emit(
+        EMIT_PRESET_LIST_CHANGED,
+        PresetListChangedPayload {
+            path: parent_preset_path(path),
+        },
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_preset_locked_cmd(app: AppHandle, name: String, locked: bool) -> Result<(), String> {
+    set_preset_locked(&app, &name, locked).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_preset_metadata_cmd(app: AppHandle, name: String) -> Result<PresetMetadata, String> {
+    if !is_valid_preset_name(&app, &name) {
+        return Err(format!("Invalid preset name: {}", name));
+    }
+    Ok(read_preset_metadata(&app, &name))
+}
+
+#[tauri::command]
+pub fn set_preset_metadata_cmd(
+    app: AppHandle,
+    name: String,
+    metadata: PresetMetadataEdit,
+) -> Result<PresetMetadata, String> {
+    set_preset_metadata(&app, &name, metadata).map_err(|e| e.to_string())
+}
+
+/// Find presets having all of `tags`, under folder `path` or in all workspaces.
+#[tauri::command]
+pub fn find_presets_by_tags_cmd(
+    app: AppHandle,
+    tags: Vec<String>,
+    path: Option<String>,
+) -> Result<Vec<TaggedPreset>, String> {
+    find_presets_by_tags(&app, &tags, path.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_preset_tags_cmd(app: AppHandle, path: Option<String>) -> Result<Vec<TagCount>, String> {
+    list_tags(&app, path.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_folder_read_only_cmd(
+    app: AppHandle,
+    path: String,
+    read_only: bool,
+) -> Result<(), String> {
+    set_folder_read_only(&app, &path, read_only).map_err(|e| e.to_string())
+}
@@ -1,7 +1,22 @@
 pub mod app;
+pub mod autosave;
+pub mod bulk;
 pub mod autostart;
+pub mod bundle;
+pub mod diff;
+pub mod layout;
+pub mod metadata;
 pub mod observer;
+pub mod schema;
+pub mod search;
 pub mod settings;
 pub mod shortcut;
+pub mod templates;
+pub mod trash;
 pub mod tray;
+pub mod tree;
+pub mod validate;
+pub mod versions;
+pub mod watcher;
 pub mod window;
+pub mod workspace;
@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context as _, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Format version written into every saved preset.
+pub(crate) const PRESET_FORMAT_VERSION: u64 = 1;
+
+/// Key of the format version in preset files. Files without it are version 0.
+const FORMAT_VERSION_KEY: &str = "format_version";
+
+/// Upgrades a preset object from the previous version to `version`,
+/// recording a human-readable line for each change.
+struct Migration {
+    version: u64,
+    migrate: fn(&mut Map<String, Value>, &mut Vec<String>),
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    migrate: migrate_v1,
+}];
+
+/// What was changed when a preset was upgraded.
+#[derive(Clone, Debug, Serialize)]
+pub struct MigrationReport {
+    from_version: u64,
+    to_version: u64,
+    changes: Vec<String>,
+
+    /// Copy of the original file, if the preset was migrated on disk.
+    backup: Option<String>,
+}
+
+/// Version 1: agents or connections may be missing entirely.
+fn migrate_v1(preset: &mut Map<String, Value>, changes: &mut Vec<String>) {
+    for key in ["agents", "connections"] {
+        if !preset.get(key).is_some_and(Value::is_array) {
+            preset.insert(key.to_string(), Value::Array(Vec::new()));
+            changes.push(format!("Added missing \"{}\"", key));
+        }
+    }
+
+    // Give every agent an ID, as connections refer to agents by ID
+    if let Some(Value::Array(agents)) = preset.get_mut("agents") {
+        let mut taken: HashSet<String> = agents
+            .iter()
+            .filter_map(|a| a.get("id").and_then(Value::as_str))
+            .map(|id| id.to_string())
+            .collect();
+        let mut next = 1;
+        for agent in agents.iter_mut().filter_map(Value::as_object_mut) {
+            if agent.get("id").and_then(Value::as_str).is_some() {
+                continue;
+            }
+            let def_name = agent
+                .get("def_name")
+                .and_then(Value::as_str)
+                .unwrap_or("agent")
+                .to_string();
+            let mut id = format!("migrated-{}", next);
+            while taken.contains(&id) {
+                next += 1;
+                id = format!("migrated-{}", next);
+            }
+            next += 1;
+            taken.insert(id.clone());
+            agent.insert("id".to_string(), Value::String(id.clone()));
+            changes.push(format!("Assigned ID {} to agent {}", id, def_name));
+        }
+    }
+}
+
+fn format_version(preset: &Map<String, Value>) -> u64 {
+    preset
+        .get(FORMAT_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+/// Add the current format version to a preset serialized by `PresetSpec::to_json`.
+pub(crate) fn stamp_version(json: &str) -> Result<String> {
+    let mut value: Value = serde_json::from_str(json).context("Failed to parse preset JSON")?;
+    let Some(preset) = value.as_object_mut() else {
+        bail!("Preset JSON is not an object");
+    };
+    preset.insert(
+        FORMAT_VERSION_KEY.to_string(),
+        Value::from(PRESET_FORMAT_VERSION),
+    );
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// Upgrade preset JSON to the current format version.
+/// Returns `None` if it is already current.
+pub(crate) fn migrate_json(content: &str) -> Result<Option<(String, MigrationReport)>> {
+    let mut value: Value = serde_json::from_str(content).context("Failed to parse preset JSON")?;
+    let Some(preset) = value.as_object_mut() else {
+        bail!("Preset JSON is not an object");
+    };
+
+    let from_version = format_version(preset);
+    if from_version == PRESET_FORMAT_VERSION {
+        return Ok(None);
+    }
+    if from_version > PRESET_FORMAT_VERSION {
+        bail!(
+            "Preset format version {} is newer than supported ({}). Please update the app.",
+            from_version,
+            PRESET_FORMAT_VERSION
+        );
+    }
+
+    let mut changes = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.version > from_version) {
+        (migration.migrate)(preset, &mut changes);
+    }
+    preset.insert(
+        FORMAT_VERSION_KEY.to_string(),
+        Value::from(PRESET_FORMAT_VERSION),
+    );
+
+    let json = serde_json::to_string_pretty(&value)?;
+    Ok(Some((
+        json,
+        MigrationReport {
+            from_version,
+            to_version: PRESET_FORMAT_VERSION,
+            changes,
+            backup: None,
+        },
+    )))
+}
+
+/// Copy of a preset file from before a migration, e.g. "MyPreset.json.v0.bak".
+fn migration_backup_path(path: &Path, from_version: u64) -> PathBuf {
+    path.with_extension(format!("json.v{}.bak", from_version))
+}
+
+/// Upgrade a preset file in place, keeping a copy of the original.
+/// Files that only lack the version stamp are left alone; it is added on the next save.
+/// Files that are not valid JSON are left alone for the backup recovery to handle.
+pub(crate) fn migrate_preset_file(
+    path: &Path,
+    write: impl FnOnce(&Path, &str) -> Result<()>,
+) -> Result<Option<MigrationReport>> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    if !matches!(serde_json::from_str(&content), Ok(Value::Object(_))) {
+        return Ok(None);
+    }
+    let Some((json, mut report)) = migrate_json(&content)? else {
+        return Ok(None);
+    };
+    if report.changes.is_empty() {
+        return Ok(None);
+    }
+
+    let backup = migration_backup_path(path, report.from_version);
+    std::fs::copy(path, &backup)
+        .with_context(|| format!("Failed to back up preset before migration: {:?}", path))?;
+    write(path, &json)?;
+
+    report.backup = Some(backup.to_string_lossy().to_string());
+    Ok(Some(report))
+}
@@ -0,0 +1,288 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use modular_agent_core::PresetSpec;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::modular_agent_desktop::app::{list_presets_recursive, preset_path};
+use crate::modular_agent_desktop::workspace::{workspace_prefix, workspace_roots};
+
+/// Strings longer than this (e.g. embedded images) are not indexed.
+const MAX_INDEXED_TEXT_LEN: usize = 10_000;
+
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+/// Indexed fields of each preset, keyed by preset name.
+type PresetIndex = HashMap<String, Vec<IndexedField>>;
+
+/// Index of all preset files, kept in the app state.
+pub struct PresetSearchIndex {
+    /// `None` until the first search, when it is built from disk.
+    index: Mutex<Option<PresetIndex>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FieldKind {
+    Name,
+    Definition,
+    Title,
+    Description,
+    ConfigKey,
+    ConfigValue,
+}
+
+impl FieldKind {
+    fn weight(self) -> u32 {
+        match self {
+            FieldKind::Name => 10,
+            FieldKind::Title => 6,
+            FieldKind::Definition => 5,
+            FieldKind::Description => 3,
+            FieldKind::ConfigKey => 2,
+            FieldKind::ConfigValue => 2,
+        }
+    }
+}
+
+struct IndexedField {
+    agent_id: Option<String>,
+    kind: FieldKind,
+
+    /// Lowercased text.
+    text: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SearchMatch {
+    name: String,
+    score: u32,
+
+    /// Agents whose fields matched the query.
+    agent_ids: Vec<String>,
+}
+
+fn push_field(fields: &mut Vec<IndexedField>, agent_id: Option<&str>, kind: FieldKind, text: &str) {
+    if text.is_empty() || text.len() > MAX_INDEXED_TEXT_LEN || text.starts_with("data:") {
+        return;
+    }
+    fields.push(IndexedField {
+        agent_id: agent_id.map(|s| s.to_string()),
+        kind,
+        text: text.to_lowercase(),
+    });
+}
+
+fn collect_config_fields(value: &Value, agent_id: Option<&str>, fields: &mut Vec<IndexedField>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                push_field(fields, agent_id, FieldKind::ConfigKey, key);
+                collect_config_fields(value, agent_id, fields);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_config_fields(value, agent_id, fields);
+            }
+        }
+        Value::String(s) => push_field(fields, agent_id, FieldKind::ConfigValue, s),
+        _ => {}
+    }
+}
+
+fn extract_fields(name: &str, spec: &PresetSpec) -> Vec<IndexedField> {
+    let mut fields = Vec::new();
+    push_field(&mut fields, None, FieldKind::Name, name);
+
+    let Ok(value) = serde_json::to_value(spec) else {
+        return fields;
+    };
+    if let Some(description) = value.get("description").and_then(Value::as_str) {
+        push_field(&mut fields, None, FieldKind::Description, description);
+    }
+
+    let agents = value.get("agents").and_then(Value::as_array);
+    for agent in agents.into_iter().flatten() {
+        let agent_id = agent.get("id").and_then(Value::as_str);
+        if let Some(def_name) = agent.get("def_name").and_then(Value::as_str) {
+            push_field(&mut fields, agent_id, FieldKind::Definition, def_name);
+        }
+        if let Some(title) = agent.get("title").and_then(Value::as_str) {
+            push_field(&mut fields, agent_id, FieldKind::Title, title);
+        }
+        if let Some(description) = agent.get("description").and_then(Value::as_str) {
+            push_field(&mut fields, agent_id, FieldKind::Description, description);
+        }
+        if let Some(configs) = agent.get("configs") {
+            collect_config_fields(configs, agent_id, &mut fields);
+        }
+    }
+
+    fields
+}
+
+fn read_fields(app: &AppHandle, name: &str) -> Option<Vec<IndexedField>> {
+    let path = preset_path(app, name).ok()?;
+    let content = std::fs::read_to_string(path).ok()?;
+    let spec = PresetSpec::from_json(&content).ok()?;
+    Some(extract_fields(name, &spec))
+}
+
+fn build_index(app: &AppHandle) -> Result<PresetIndex> {
+    let mut index = HashMap::new();
+    for (workspace, _) in workspace_roots(app)? {
+        for name in list_presets_recursive(app, &workspace_prefix(&workspace))? {
+            match read_fields(app, &name) {
+                Some(fields) => {
+                    index.insert(name, fields);
+                }
+                None => log::warn!("Skipping unreadable preset in search index: {}", name),
+            }
+        }
+    }
+    log::debug!("Built preset search index: {} presets", index.len());
+    Ok(index)
+}
+
+pub fn init(app: &AppHandle) {
+    app.manage(PresetSearchIndex {
+        index: Mutex::new(None),
+    });
+}
+
+/// Apply `f` to the index if it has been built. Updates before the first search are
+/// not needed, since the index is then built from disk.
+fn update_index(app: &AppHandle, f: impl FnOnce(&mut PresetIndex)) {
+    let search_index = app.state::<PresetSearchIndex>();
+    let mut index = search_index.index.lock().unwrap();
+    if let Some(index) = index.as_mut() {
+        f(index);
+    }
+}
+
+/// Index a saved preset.
+pub(crate) fn index_preset(app: &AppHandle, name: &str, spec: &PresetSpec) {
+    update_index(app, |index| {
+        index.insert(name.to_string(), extract_fields(name, spec));
+    });
+}
+
+/// Re-read a preset from disk, e.g. after an external edit.
+pub(crate) fn refresh_preset(app: &AppHandle, name: &str) {
+    update_index(app, |index| match read_fields(app, name) {
+        Some(fields) => {
+            index.insert(name.to_string(), fields);
+        }
+        None => {
+            index.remove(name);
+        }
+    });
+}
+
+pub(crate) fn remove_preset(app: &AppHandle, name: &str) {
+    update_index(app, |index| {
+        index.remove(name);
+    });
+}
+
+/// Remove all presets inside a folder. `prefix` ends with '/'.
+pub(crate) fn remove_prefix(app: &AppHandle, prefix: &str) {
+    update_index(app, |index| {
+        index.retain(|name, _| !name.starts_with(prefix));
+    });
+}
+
+pub(crate) fn rename_preset(app: &AppHandle, old_name: &str, new_name: &str) {
+    update_index(app, |index| {
+        index.remove(old_name);
+        if let Some(fields) = read_fields(app, new_name) {
+            index.insert(new_name.to_string(), fields);
+        }
+    });
+}
+
+/// Follow a folder move. Both prefixes end with '/'.
+pub(crate) fn rename_prefix(app: &AppHandle, old_prefix: &str, new_prefix: &str) {
+    update_index(app, |index| {
+        let moved: Vec<String> = index
+            .keys()
+            .filter(|name| name.starts_with(old_prefix))
+            .cloned()
+            .collect();
+        for old_name in moved {
+            index.remove(&old_name);
+            let new_name = format!("{}{}", new_prefix, &old_name[old_prefix.len()..]);
+            if let Some(fields) = read_fields(app, &new_name) {
+                index.insert(new_name, fields);
+            }
+        }
+    });
+}
+
+/// Drop the whole index; it is rebuilt on the next search.
+pub(crate) fn invalidate(app: &AppHandle) {
+    let search_index = app.state::<PresetSearchIndex>();
+    let mut index = search_index.index.lock().unwrap();
+    *index = None;
+}
+
+/// Search presets for all whitespace-separated terms of `query`, best matches first.
+pub(crate) fn search(app: &AppHandle, query: &str, limit: usize) -> Result<Vec<SearchMatch>> {
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let search_index = app.state::<PresetSearchIndex>();
+    let mut index = search_index.index.lock().unwrap();
+    if index.is_none() {
+        *index = Some(build_index(app)?);
+    }
+    let Some(index) = index.as_ref() else {
+        return Ok(Vec::new());
+    };
+
+    let mut matches = Vec::new();
+    'presets: for (name, fields) in index {
+        let mut score = 0;
+        let mut agent_ids = BTreeSet::new();
+        for term in &terms {
+            let mut matched = false;
+            for field in fields.iter().filter(|f| f.text.contains(term.as_str())) {
+                matched = true;
+                score += field.kind.weight();
+                if field.text == *term {
+                    score += field.kind.weight();
+                }
+                if let Some(agent_id) = &field.agent_id {
+                    agent_ids.insert(agent_id.clone());
+                }
+            }
+            if !matched {
+                continue 'presets;
+            }
+        }
+        matches.push(SearchMatch {
+            name: name.clone(),
+            score,
+            agent_ids: agent_ids.into_iter().collect(),
+        });
+    }
+
+    matches.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    matches.truncate(limit);
+
+    Ok(matches)
+}
+
+#[tauri::command]
+pub async fn search_presets_cmd(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchMatch>, String> {
+    search(&app, &query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT)).map_err(|e| e.to_string())
+}
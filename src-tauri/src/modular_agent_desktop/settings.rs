@@ -11,7 +11,7 @@ use tauri::{AppHandle, Manager, State};
 use tauri_plugin_modular_agent::ModularAgentExt;
 use tauri_plugin_store::StoreExt;
 
-const SETTINGS_JSON: &str = "settings.json";
+use crate::modular_agent_desktop::{layout, workspace};
 
 pub fn init(app: &AppHandle) -> Result<()> {
     init_core_settings(app)?;
@@ -19,7 +19,7 @@ pub fn init(app: &AppHandle) -> Result<()> {
 }
 
 pub fn save(app: &AppHandle) -> Result<()> {
-    let store = app.store(SETTINGS_JSON)?;
+    let store = app.store(layout::settings_store_path())?;
 
     let core_settings = app.state::<Mutex<CoreSettings>>();
     let settings_json;
@@ -73,6 +73,24 @@ pub struct CoreSettings {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_history_length: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_preset_versions: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trash_retention_days: Option<u32>,
+
+    /// Data directory used instead of `~/.modular_agent`. Applied on the next start.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<String>,
+
+    /// Additional preset workspaces, mapping names to absolute directories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspaces: Option<HashMap<String, String>>,
+
+    /// Seconds between autosaves of open presets with unsaved edits. Disabled if not set or 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autosave_interval: Option<u64>,
 }
 
 impl Default for CoreSettings {
@@ -102,12 +120,17 @@ impl Default for CoreSettings {
             show_grid: None,
             grid_gap: None,
             max_history_length: None,
+            max_preset_versions: None,
+            trash_retention_days: None,
+            data_dir: None,
+            workspaces: None,
+            autosave_interval: None,
         }
     }
 }
 
 fn init_core_settings(app: &AppHandle) -> Result<()> {
-    let store = app.store(SETTINGS_JSON)?;
+    let store = app.store(layout::settings_store_path())?;
 
     let core_settings: CoreSettings;
     if let Some(store_value) = store.get("core") {
@@ -129,7 +152,7 @@ fn init_core_settings(app: &AppHandle) -> Result<()> {
 }
 
 pub fn load_agent_global_configs(app: &AppHandle) -> Result<()> {
-    let store = app.store(SETTINGS_JSON)?;
+    let store = app.store(layout::settings_store_path())?;
 
     if let Some(store_value) = store.get("agents") {
         let mut global_configs_map = app.ma().get_global_configs_map();
@@ -184,19 +207,26 @@ pub fn set_core_settings_cmd(
     }
 
     // Merge new settings into existing settings
+    let workspaces_changed;
     if new_settings.is_object() {
         let mut settings = settings.lock().unwrap();
         let mut value = serde_json::to_value(&*settings)
             .map_err(|e| format!("Failed to serialize current settings: {}", e))?;
         json_merge(&mut value, new_settings);
+        let old_workspaces = settings.workspaces.take();
         *settings = serde_json::from_value(value)
             .map_err(|e| format!("Failed to deserialize new settings: {}", e))?;
+        workspaces_changed = settings.workspaces != old_workspaces;
     } else {
         return Err("Invalid settings format".to_string());
     }
 
     save(&app).map_err(|e| e.to_string())?;
 
+    if workspaces_changed {
+        workspace::reload(&app).map_err(|e| e.to_string())?;
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use modular_agent_core::PresetSpec;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::modular_agent_desktop::app::{
+    collect_preset_names, folder_path, is_valid_preset_name, modular_agent_dir, parent_preset_path,
+    preset_path_exists, ModularAgentApp, PresetListChangedPayload, EMIT_PRESET_LIST_CHANGED,
+};
+use crate::modular_agent_desktop::schema;
+
+static MODULAR_AGENT_TEMPLATES_PATH: &str = "templates";
+
+/// Key of the template declaration in a template file.
+/// The rest of the file is an ordinary preset.
+const TEMPLATE_KEY: &str = "template";
+
+/// A template file looks like a preset with an extra "template" object:
+///
+/// ```json
+/// {
+///   "template": {
+///     "description": "Chat with a local model",
+///     "params": [{ "name": "model", "default": "llama3" }]
+///   },
+///   "agents": [{ "configs": { "model": "{{model}}" }, ... }],
+///   ...
+/// }
+/// ```
+///
+/// Placeholders are substituted in the agent config values only.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct TemplateDeclaration {
+    #[serde(default)]
+    description: Option<String>,
+
+    #[serde(default)]
+    params: Vec<TemplateParam>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TemplateParam {
+    /// Placeholder name, used as `{{name}}` in config values.
+    name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    /// Value used when the parameter is not given. Parameters without one are required.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<Value>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TemplateInfo {
+    /// Template name, with '/' for folders under the templates directory.
+    name: String,
+    description: Option<String>,
+    params: Vec<TemplateParam>,
+}
+
+/// Directory holding the templates. e.g., ~/.modular_agent/templates/
+fn templates_dir() -> Result<PathBuf> {
+    Ok(modular_agent_dir()?.join(MODULAR_AGENT_TEMPLATES_PATH))
+}
+
+fn template_path(app: &AppHandle, name: &str) -> Result<PathBuf> {
+    // Template names follow the rules of preset names, without workspaces
+    if name.contains(':') || !is_valid_preset_name(app, name) {
+        bail!("Invalid template name: {}", name);
+    }
+    let mut path = templates_dir()?;
+    for segment in name.split('/') {
+        path = path.join(segment);
+    }
+    Ok(path.with_extension("json"))
+}
+
+/// Read a template, split into its declaration and the preset JSON.
+fn read_template(app: &AppHandle, name: &str) -> Result<(TemplateDeclaration, Value)> {
+    let path = template_path(app, name)?;
+    if !path.is_file() {
+        bail!("Template not found: {}", name);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read template: {:?}", path))?;
+    let content = match schema::migrate_json(&content)? {
+        Some((json, _)) => json,
+        None => content,
+    };
+
+    let mut preset: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse template: {:?}", path))?;
+    let declaration = match preset.as_object_mut().and_then(|p| p.remove(TEMPLATE_KEY)) {
+        Some(value) => serde_json::from_value(value)
+            .with_context(|| format!("Invalid template declaration: {}", name))?,
+        None => TemplateDeclaration::default(),
+    };
+    Ok((declaration, preset))
+}
+
+pub(crate) fn list_templates(app: &AppHandle) -> Result<Vec<TemplateInfo>> {
+    let dir = templates_dir()?;
+    let mut names = Vec::new();
+    if dir.is_dir() {
+        collect_preset_names(&dir, "", &mut names)?;
+    }
+    names.sort();
+
+    let mut templates = Vec::new();
+    for name in names {
+        match read_template(app, &name) {
+            Ok((declaration, _)) => templates.push(TemplateInfo {
+                name,
+                description: declaration.description,
+                params: declaration.params,
+            }),
+            Err(e) => log::warn!("Skipping template {}: {}", name, e),
+        }
+    }
+    Ok(templates)
+}
+
+/// Placeholders in a string as (start, end, name). Whitespace around the name is ignored,
+/// e.g. "Model: {{ model }}" has one placeholder named "model".
+fn placeholders(s: &str) -> Vec<(usize, usize, &str)> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = s[pos..].find("{{").map(|i| pos + i) {
+        let Some(end) = s[start + 2..].find("}}").map(|i| start + i + 4) else {
+            break;
+        };
+        found.push((start, end, s[start + 2..end - 2].trim()));
+        pos = end;
+    }
+    found
+}
+
+/// Replace the placeholders in a string. A string that is a single placeholder
+/// takes the parameter value as is, so numbers and booleans keep their type.
+fn substitute_str(s: &str, values: &HashMap<String, Value>) -> Value {
+    let placeholders = placeholders(s);
+    if let [(0, end, name)] = placeholders.as_slice() {
+        if let (true, Some(value)) = (*end == s.len(), values.get(*name)) {
+            return value.clone();
+        }
+    }
+
+    // Unknown placeholders are kept as they are
+    let mut result = String::new();
+    let mut pos = 0;
+    for (start, end, name) in placeholders {
+        let Some(value) = values.get(name) else {
+            continue;
+        };
+        result.push_str(&s[pos..start]);
+        match value {
+            Value::String(v) => result.push_str(v),
+            v => result.push_str(&v.to_string()),
+        }
+        pos = end;
+    }
+    result.push_str(&s[pos..]);
+    Value::String(result)
+}
+
+fn substitute(value: &mut Value, values: &HashMap<String, Value>) {
+    match value {
+        Value::String(s) => *value = substitute_str(s, values),
+        Value::Array(items) => items.iter_mut().for_each(|v| substitute(v, values)),
+        Value::Object(map) => map.values_mut().for_each(|v| substitute(v, values)),
+        _ => {}
+    }
+}
+
+/// Build a preset from a template, filling in the parameters.
+pub(crate) fn instantiate_template(
+    app: &AppHandle,
+    name: &str,
+    params: HashMap<String, Value>,
+) -> Result<PresetSpec> {
+    let (declaration, mut preset) = read_template(app, name)?;
+
+    for key in params.keys() {
+        if !declaration.params.iter().any(|p| &p.name == key) {
+            bail!("Unknown template parameter: {}", key);
+        }
+    }
+    let mut values = HashMap::new();
+    for param in &declaration.params {
+        let value = params
+            .get(&param.name)
+            .or(param.default.as_ref())
+            .with_context(|| format!("Missing template parameter: {}", param.name))?;
+        values.insert(param.name.clone(), value.clone());
+    }
+
+    let agents = preset.get_mut("agents").and_then(Value::as_array_mut);
+    for agent in agents.into_iter().flatten() {
+        if let Some(configs) = agent.get_mut("configs") {
+            substitute(configs, &values);
+        }
+    }
+
+    PresetSpec::from_json(&serde_json::to_string(&preset)?)
+        .map_err(|e| anyhow!("Failed to parse template {}: {}", name, e))
+}
+
+#[tauri::command]
+pub fn list_templates_cmd(app: AppHandle) -> Result<Vec<TemplateInfo>, String> {
+    list_templates(&app).map_err(|e| e.to_string())
+}
+
+/// Create a preset from a template, save it and open it. Returns the new preset ID.
+#[tauri::command]
+pub async fn new_preset_from_template_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    template: String,
+    name: String,
+    params: Option<HashMap<String, Value>>,
+) -> Result<String, String> {
+    if !is_valid_preset_name(&app, &name) {
+        return Err(format!("Invalid preset name: {}", name));
+    }
+    if preset_path_exists(&app, &name) {
+        return Err(format!("Preset already exists: {}", name));
+    }
+    let spec = instantiate_template(&app, &template, params.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+
+    let parent_dir = parent_preset_path(&name);
+    let parent_existed = parent_dir.is_empty()
+        || folder_path(&app, &parent_dir)
+            .map(|d| d.exists())
+            .unwrap_or(true);
+    asapp
+        .save_preset(&app, name.clone(), spec)
+        .map_err(|e| e.to_string())?;
+    let _ = app.emit(
+        EMIT_PRESET_LIST_CHANGED,
+        PresetListChangedPayload {
+            path: parent_dir.clone(),
+        },
+    );
+    if !parent_existed {
+        let _ = app.emit(
+            EMIT_PRESET_LIST_CHANGED,
+            PresetListChangedPayload {
+                path: parent_preset_path(&parent_dir),
+            },
+        );
+    }
+
+    asapp
+        .open_preset(&app, name)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,305 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context as _, Result};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::modular_agent_desktop::app::{
+    emit_ancestors_list_changed, folder_path, is_valid_preset_name, modular_agent_dir, move_path,
+    move_preset_sidecars, parent_preset_path, preset_basename, preset_path, unique_preset_name,
+};
+use crate::modular_agent_desktop::metadata;
+use crate::modular_agent_desktop::search;
+use crate::modular_agent_desktop::settings::CoreSettings;
+use crate::modular_agent_desktop::tree;
+use crate::modular_agent_desktop::versions;
+
+static MODULAR_AGENT_TRASH_PATH: &str = ".trash";
+const TRASH_INFO_JSON: &str = "info.json";
+
+/// Saved snapshots of a trashed preset or folder, inside its trash entry.
+const TRASH_VERSIONS_DIR: &str = ".versions";
+
+/// Days a trashed item is kept when `trash_retention_days` is not set.
+const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrashKind {
+    Preset,
+    Folder,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    id: String,
+
+    /// Original preset name or folder path.
+    name: String,
+
+    kind: TrashKind,
+
+    /// Milliseconds since the Unix epoch.
+    deleted_at: u64,
+}
+
+fn trash_dir() -> Result<PathBuf> {
+    Ok(modular_agent_dir()?.join(MODULAR_AGENT_TRASH_PATH))
+}
+
+fn trash_entry_dir(id: &str) -> Result<PathBuf> {
+    if id.is_empty() || id.contains('/') || id.contains('\\') || id.contains("..") {
+        bail!("Invalid trash entry: {}", id);
+    }
+    Ok(trash_dir()?.join(id))
+}
+
+fn now_millis() -> Result<u64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("System time is before the Unix epoch")?;
+    Ok(now.as_millis() as u64)
+}
+
+/// Create a fresh entry directory in the trash and return its ID and path.
+fn new_trash_entry(deleted_at: u64) -> Result<(String, PathBuf)> {
+    let root = trash_dir()?;
+    let mut id = deleted_at.to_string();
+    let mut i = 1;
+    while root.join(&id).exists() {
+        id = format!("{}-{}", deleted_at, i);
+        i += 1;
+    }
+    let dir = root.join(&id);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create trash entry: {:?}", dir))?;
+    Ok((id, dir))
+}
+
+fn write_entry_info(dir: &Path, entry: &TrashEntry) -> Result<()> {
+    let json = serde_json::to_string_pretty(entry)?;
+    std::fs::write(dir.join(TRASH_INFO_JSON), json)
+        .with_context(|| format!("Failed to write trash entry info: {:?}", dir))
+}
+
+fn read_entry_info(id: &str) -> Result<TrashEntry> {
+    let dir = trash_entry_dir(id)?;
+    let content = std::fs::read_to_string(dir.join(TRASH_INFO_JSON))
+        .with_context(|| format!("Trash entry not found: {}", id))?;
+    let mut entry: TrashEntry = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse trash entry info: {}", id))?;
+    entry.id = id.to_string();
+    Ok(entry)
+}
+
+/// Move a preset file (and its sidecars and versions) into the trash.
+pub(crate) fn trash_preset(name: &str, path: &Path) -> Result<()> {
+    let deleted_at = now_millis()?;
+    let (id, dir) = new_trash_entry(deleted_at)?;
+
+    let file_name = format!("{}.json", preset_basename(name));
+    let target = dir.join(&file_name);
+    move_path(path, &target)
+        .with_context(|| format!("Failed to move preset to trash: {}", name))?;
+    move_preset_sidecars(path, &target);
+    if let Err(e) = versions::take_versions(name, &dir.join(TRASH_VERSIONS_DIR)) {
+        log::warn!("Failed to move preset versions to trash: {}", e);
+    }
+
+    write_entry_info(
+        &dir,
+        &TrashEntry {
+            id,
+            name: name.to_string(),
+            kind: TrashKind::Preset,
+            deleted_at,
+        },
+    )
+}
+
+/// Move a folder (and all its contents) into the trash.
+pub(crate) fn trash_folder(path: &str, dir_path: &Path) -> Result<()> {
+    let deleted_at = now_millis()?;
+    let (id, dir) = new_trash_entry(deleted_at)?;
+
+    move_path(dir_path, &dir.join(preset_basename(path)))
+        .with_context(|| format!("Failed to move folder to trash: {}", path))?;
+    if let Err(e) = versions::take_versions(path, &dir.join(TRASH_VERSIONS_DIR)) {
+        log::warn!("Failed to move folder versions to trash: {}", e);
+    }
+
+    write_entry_info(
+        &dir,
+        &TrashEntry {
+            id,
+            name: path.to_string(),
+            kind: TrashKind::Folder,
+            deleted_at,
+        },
+    )
+}
+
+/// List trashed items, most recently deleted first.
+pub(crate) fn list_trash() -> Result<Vec<TrashEntry>> {
+    let root = trash_dir()?;
+    let mut entries = Vec::new();
+    if !root.is_dir() {
+        return Ok(entries);
+    }
+
+    let dir_entries = std::fs::read_dir(&root)
+        .with_context(|| format!("Failed to read directory: {:?}", root))?;
+    for dir_entry in dir_entries {
+        let path = dir_entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let id = path
+            .file_name()
+            .context("Failed to get trash entry name")?
+            .to_string_lossy()
+            .to_string();
+        match read_entry_info(&id) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => log::warn!("Skipping broken trash entry {}: {}", id, e),
+        }
+    }
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+
+    Ok(entries)
+}
+
+/// Pick a folder path that does not exist yet, following `unique_preset_name`.
+fn unique_folder_path(app: &AppHandle, base_path: &str) -> Result<String> {
+    if !folder_path(app, base_path)?.exists() {
+        return Ok(base_path.to_string());
+    }
+    let copy_path = format!("{} copy", base_path);
+    if !folder_path(app, &copy_path)?.exists() {
+        return Ok(copy_path);
+    }
+    for i in 2.. {
+        let path = format!("{} copy {}", base_path, i);
+        if !folder_path(app, &path)?.exists() {
+            return Ok(path);
+        }
+    }
+    unreachable!()
+}
+
+/// Move a trashed item back to its original location.
+/// The returned entry carries the restored (possibly renamed) path.
+pub(crate) fn restore(app: &AppHandle, id: &str) -> Result<TrashEntry> {
+    let mut entry = read_entry_info(id)?;
+    let dir = trash_entry_dir(id)?;
+    if !is_valid_preset_name(app, &entry.name) {
+        bail!("Invalid preset name: {}", entry.name);
+    }
+    metadata::ensure_folder_writable(app, &parent_preset_path(&entry.name))?;
+
+    match entry.kind {
+        TrashKind::Preset => {
+            let name = unique_preset_name(app, &entry.name);
+            let file_name = format!("{}.json", preset_basename(&entry.name));
+            let target = preset_path(app, &name)?;
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let source = dir.join(&file_name);
+            move_path(&source, &target)
+                .with_context(|| format!("Failed to restore preset: {}", name))?;
+            move_preset_sidecars(&source, &target);
+            search::refresh_preset(app, &name);
+            entry.name = name;
+        }
+        TrashKind::Folder => {
+            let path = unique_folder_path(app, &entry.name)?;
+            let target = folder_path(app, &path)?;
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            move_path(&dir.join(preset_basename(&entry.name)), &target)
+                .with_context(|| format!("Failed to restore folder: {}", path))?;
+            search::invalidate(app);
+            entry.name = path;
+        }
+    }
+
+    if let Err(e) = versions::put_versions(&dir.join(TRASH_VERSIONS_DIR), &entry.name) {
+        log::warn!("Failed to restore versions of {}: {}", entry.name, e);
+    }
+
+    std::fs::remove_dir_all(&dir)
+        .with_context(|| format!("Failed to remove trash entry: {}", id))?;
+    tree::invalidate(app);
+
+    Ok(entry)
+}
+
+/// Permanently delete a trashed item.
+pub(crate) fn purge(id: &str) -> Result<()> {
+    let dir = trash_entry_dir(id)?;
+    if !dir.exists() {
+        bail!("Trash entry not found: {}", id);
+    }
+    std::fs::remove_dir_all(&dir).with_context(|| format!("Failed to purge trash entry: {}", id))
+}
+
+/// Permanently delete every trashed item.
+pub(crate) fn purge_all() -> Result<()> {
+    for entry in list_trash()? {
+        purge(&entry.id)?;
+    }
+    Ok(())
+}
+
+/// Purge items that have been in the trash longer than the configured retention period.
+pub(crate) fn purge_expired(app: &AppHandle) -> Result<()> {
+    let retention_days = {
+        let core_settings = app.state::<Mutex<CoreSettings>>();
+        let settings = core_settings.lock().unwrap();
+        settings
+            .trash_retention_days
+            .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+    };
+    if retention_days == 0 {
+        return Ok(());
+    }
+
+    let cutoff = now_millis()?.saturating_sub(retention_days as u64 * MILLIS_PER_DAY);
+    for entry in list_trash()? {
+        if entry.deleted_at < cutoff {
+            log::info!("Purging trashed {:?}: {}", entry.kind, entry.name);
+            purge(&entry.id)?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_trash_cmd() -> Result<Vec<TrashEntry>, String> {
+    list_trash().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn restore_trash_entry_cmd(app: AppHandle, id: String) -> Result<TrashEntry, String> {
+    let entry = restore(&app, &id).map_err(|e| e.to_string())?;
+
+    // Emit list changed for the restored item and any recreated ancestors
+    emit_ancestors_list_changed(&app, &entry.name);
+
+    Ok(entry)
+}
+
+#[tauri::command]
+pub fn purge_trash_cmd(id: Option<String>) -> Result<(), String> {
+    match id {
+        Some(id) => purge(&id),
+        None => purge_all(),
+    }
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{bail, Context as _, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::modular_agent_desktop::app::{join_preset_path, DirEntryKind};
+use crate::modular_agent_desktop::workspace::{split_workspace, workspace_prefix, workspace_root};
+
+/// Cached trees of whole workspaces, kept in the app state.
+pub struct PresetTreeCache {
+    /// Trees keyed by workspace name.
+    /// Built on the first request, and dropped after our own file operations.
+    trees: Mutex<HashMap<String, Vec<PresetTreeNode>>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PresetTreeNode {
+    kind: DirEntryKind,
+
+    /// Base name of the folder or preset.
+    name: String,
+
+    /// Full folder path or preset name.
+    path: String,
+
+    /// Entries of a folder, folders first.
+    /// `None` for presets and for folders beyond the depth limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<PresetTreeNode>>,
+}
+
+fn build_nodes(dir: &Path, prefix: &str) -> Result<Vec<PresetTreeNode>> {
+    let mut nodes = Vec::new();
+    let dir_entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read directory: {:?}", dir))?;
+    for entry in dir_entries {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        if file_name.starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            let node_path = join_preset_path(prefix, &file_name);
+            let children = build_nodes(&path, &node_path)?;
+            nodes.push(PresetTreeNode {
+                kind: DirEntryKind::Folder,
+                name: file_name,
+                path: node_path,
+                children: Some(children),
+            });
+        } else if path.is_file() && path.extension().unwrap_or_default() == "json" {
+            let stem = path
+                .file_stem()
+                .context("Failed to get file stem")?
+                .to_string_lossy()
+                .to_string();
+            let node_path = join_preset_path(prefix, &stem);
+            nodes.push(PresetTreeNode {
+                kind: DirEntryKind::Preset,
+                name: stem,
+                path: node_path,
+                children: None,
+            });
+        }
+    }
+    nodes.sort_by(|a, b| {
+        let a_folder = matches!(a.kind, DirEntryKind::Folder);
+        let b_folder = matches!(b.kind, DirEntryKind::Folder);
+        b_folder.cmp(&a_folder).then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(nodes)
+}
+
+fn build_tree(app: &AppHandle, workspace: &str) -> Result<Vec<PresetTreeNode>> {
+    let root = workspace_root(app, workspace)?;
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+    let nodes = build_nodes(&root, &workspace_prefix(workspace))?;
+    log::debug!(
+        "Built preset tree of workspace {:?}: {} top-level entries",
+        workspace,
+        nodes.len()
+    );
+    Ok(nodes)
+}
+
+/// Copy `nodes`, cutting off folders more than `depth` levels down.
+fn truncate_nodes(nodes: &[PresetTreeNode], depth: Option<usize>) -> Vec<PresetTreeNode> {
+    if depth == Some(0) {
+        return Vec::new();
+    }
+    nodes
+        .iter()
+        .map(|node| {
+            let children = match (&node.children, depth) {
+                (Some(_), Some(1)) => None,
+                (Some(children), _) => Some(truncate_nodes(children, depth.map(|d| d - 1))),
+                (None, _) => None,
+            };
+            PresetTreeNode {
+                kind: node.kind,
+                name: node.name.clone(),
+                path: node.path.clone(),
+                children,
+            }
+        })
+        .collect()
+}
+
+pub fn init(app: &AppHandle) {
+    app.manage(PresetTreeCache {
+        trees: Mutex::new(HashMap::new()),
+    });
+}
+
+/// Drop the cached trees; they are rebuilt on the next request.
+pub(crate) fn invalidate(app: &AppHandle) {
+    let cache = app.state::<PresetTreeCache>();
+    let mut trees = cache.trees.lock().unwrap();
+    trees.clear();
+}
+
+/// Return the entries under folder `path`, recursively up to `depth` levels.
+pub(crate) fn get_tree(
+    app: &AppHandle,
+    path: &str,
+    depth: Option<usize>,
+) -> Result<Vec<PresetTreeNode>> {
+    let (workspace, rel) = split_workspace(path);
+    if rel.starts_with("/") || rel.contains("..") {
+        bail!("Invalid path: {}", path);
+    }
+
+    let cache = app.state::<PresetTreeCache>();
+    let mut trees = cache.trees.lock().unwrap();
+    if !trees.contains_key(workspace) {
+        trees.insert(workspace.to_string(), build_tree(app, workspace)?);
+    }
+    let Some(mut nodes) = trees.get(workspace).map(|t| t.as_slice()) else {
+        return Ok(Vec::new());
+    };
+
+    if !rel.is_empty() {
+        for segment in rel.split('/') {
+            let folder = nodes
+                .iter()
+                .find(|n| matches!(n.kind, DirEntryKind::Folder) && n.name == segment);
+            match folder.and_then(|f| f.children.as_deref()) {
+                Some(children) => nodes = children,
+                None => return Ok(Vec::new()),
+            }
+        }
+    }
+
+    Ok(truncate_nodes(nodes, depth))
+}
+
+#[tauri::command]
+pub async fn get_preset_tree_cmd(
+    app: AppHandle,
+    path: String,
+    depth: Option<usize>,
+) -> Result<Vec<PresetTreeNode>, String> {
+    get_tree(&app, &path, depth).map_err(|e| e.to_string())
+}
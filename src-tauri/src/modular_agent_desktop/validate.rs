@@ -0,0 +1,323 @@
+use std::collections::{HashMap, HashSet};
+
+use modular_agent_core::PresetSpec;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_modular_agent::ModularAgentExt;
+
+/// Handle prefix of connections into an agent config, e.g. "config:model".
+const CONFIG_HANDLE_PREFIX: &str = "config:";
+
+/// Config keys that commonly name MCP tools. Definitions do not declare which
+/// configs refer to tools, so unregistered tools are only reported as warnings.
+const TOOL_CONFIG_KEYS: &[&str] = &["tool", "tools", "tool_name", "tool_names"];
+
+/// Tools registered from `mcp.json` at startup, kept in the app state.
+pub struct McpTools(HashSet<String>);
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidationIssue {
+    severity: Severity,
+
+    /// Machine-readable kind of the problem, e.g. "unknown_definition".
+    code: &'static str,
+
+    message: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agent_id: Option<String>,
+
+    /// Index into `connections`, for problems with a connection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    connection: Option<usize>,
+}
+
+impl ValidationIssue {
+    pub(crate) fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Record the tools registered from `mcp.json`.
+pub(crate) fn set_mcp_tools(app: &AppHandle, tools: impl IntoIterator<Item = String>) {
+    app.manage(McpTools(tools.into_iter().collect()));
+}
+
+struct AgentPorts {
+    def_name: String,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    configs: Option<serde_json::Map<String, Value>>,
+}
+
+fn string_array(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|a| {
+            a.iter()
+                .filter_map(Value::as_str)
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_empty_value(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// Tool names referenced by the configs of an agent.
+fn referenced_tools(configs: &serde_json::Map<String, Value>) -> Vec<String> {
+    let mut tools = Vec::new();
+    for key in TOOL_CONFIG_KEYS {
+        match configs.get(*key) {
+            Some(Value::String(s)) if !s.trim().is_empty() => tools.push(s.trim().to_string()),
+            Some(Value::Array(values)) => tools.extend(
+                values
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .filter(|s| !s.trim().is_empty())
+                    .map(|s| s.trim().to_string()),
+            ),
+            _ => {}
+        }
+    }
+    tools
+}
+
+/// Inspect a preset and report every problem found, errors first.
+pub(crate) fn validate(app: &AppHandle, spec: &PresetSpec) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |severity, code, message: String, agent_id: Option<&str>, connection| {
+        issues.push(ValidationIssue {
+            severity,
+            code,
+            message,
+            agent_id: agent_id.map(|s| s.to_string()),
+            connection,
+        });
+    };
+
+    let definitions = serde_json::to_value(app.ma().get_agent_definitions()).unwrap_or_default();
+    let global_configs =
+        serde_json::to_value(app.ma().get_global_configs_map()).unwrap_or_default();
+    let spec = serde_json::to_value(spec).unwrap_or_default();
+    // Without `mcp.json`, tools cannot be checked
+    let mcp_tools = app.try_state::<McpTools>();
+
+    // Agents and their ports. Ports listed in the preset take precedence over the definition.
+    let mut agents: HashMap<String, AgentPorts> = HashMap::new();
+    let agent_values = spec.get("agents").and_then(Value::as_array);
+    for agent in agent_values.into_iter().flatten() {
+        let id = agent.get("id").and_then(Value::as_str).unwrap_or_default();
+        let def_name = agent
+            .get("def_name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let definition = definitions.get(def_name);
+        if definition.is_none() {
+            issue(
+                Severity::Error,
+                "unknown_definition",
+                format!("Unknown agent definition: {}", def_name),
+                Some(id),
+                None,
+            );
+        }
+        if agents.contains_key(id) {
+            issue(
+                Severity::Error,
+                "duplicate_agent_id",
+                format!("Duplicate agent ID: {}", id),
+                Some(id),
+                None,
+            );
+        }
+
+        let ports = |key: &str| match agent.get(key) {
+            Some(Value::Array(_)) => string_array(agent.get(key)),
+            _ => string_array(definition.and_then(|d| d.get(key))),
+        };
+        let configs = agent.get("configs").and_then(Value::as_object).cloned();
+
+        // MCP tools referenced but not registered
+        if let (Some(configs), Some(mcp_tools)) = (&configs, &mcp_tools) {
+            for tool in referenced_tools(configs) {
+                if !mcp_tools.0.contains(&tool) {
+                    issue(
+                        Severity::Warning,
+                        "unknown_tool",
+                        format!("MCP tool is not registered: {}", tool),
+                        Some(id),
+                        None,
+                    );
+                }
+            }
+        }
+
+        agents.insert(
+            id.to_string(),
+            AgentPorts {
+                def_name: def_name.to_string(),
+                inputs: ports("inputs"),
+                outputs: ports("outputs"),
+                configs,
+            },
+        );
+    }
+
+    // Connections to missing agents or ports
+    let mut connected_inputs: HashSet<(String, String)> = HashSet::new();
+    let connection_values = spec.get("connections").and_then(Value::as_array);
+    for (i, connection) in connection_values.into_iter().flatten().enumerate() {
+        let field = |key: &str| {
+            connection
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        };
+        let (source, source_handle) = (field("source"), field("source_handle"));
+        let (target, target_handle) = (field("target"), field("target_handle"));
+
+        match agents.get(source) {
+            None => issue(
+                Severity::Error,
+                "missing_agent",
+                format!("Connection from a missing agent: {}", source),
+                None,
+                Some(i),
+            ),
+            // Agents may emit on outputs their definition does not declare, e.g. errors
+            Some(agent) if !agent.outputs.iter().any(|o| o == source_handle) => issue(
+                Severity::Warning,
+                "missing_port",
+                format!(
+                    "Connection from an undeclared output {} of agent {}",
+                    source_handle, agent.def_name
+                ),
+                Some(source),
+                Some(i),
+            ),
+            _ => {}
+        }
+
+        match agents.get(target) {
+            None => issue(
+                Severity::Error,
+                "missing_agent",
+                format!("Connection to a missing agent: {}", target),
+                None,
+                Some(i),
+            ),
+            Some(agent) => {
+                let exists = match target_handle.strip_prefix(CONFIG_HANDLE_PREFIX) {
+                    Some(key) => agent.configs.as_ref().is_some_and(|c| c.contains_key(key)),
+                    None => agent.inputs.iter().any(|input| input == target_handle),
+                };
+                if exists {
+                    connected_inputs.insert((target.to_string(), target_handle.to_string()));
+                } else {
+                    issue(
+                        Severity::Error,
+                        "missing_port",
+                        format!(
+                            "Connection to a missing input {} of agent {}",
+                            target_handle, agent.def_name
+                        ),
+                        Some(target),
+                        Some(i),
+                    );
+                }
+            }
+        }
+    }
+
+    // Disconnected inputs. An agent with inputs but no incoming connection never runs.
+    let mut agent_ids: Vec<&String> = agents.keys().collect();
+    agent_ids.sort();
+    for id in agent_ids {
+        let agent = &agents[id];
+        let unconnected: Vec<&String> = agent
+            .inputs
+            .iter()
+            .filter(|input| !connected_inputs.contains(&(id.clone(), input.to_string())))
+            .collect();
+        if unconnected.is_empty() {
+            continue;
+        }
+        if unconnected.len() == agent.inputs.len() {
+            issue(
+                Severity::Warning,
+                "disconnected_agent",
+                format!("No inputs of agent {} are connected", agent.def_name),
+                Some(id),
+                None,
+            );
+        } else {
+            for input in unconnected {
+                issue(
+                    Severity::Info,
+                    "disconnected_input",
+                    format!(
+                        "Input {} of agent {} is not connected",
+                        input, agent.def_name
+                    ),
+                    Some(id),
+                    None,
+                );
+            }
+        }
+    }
+
+    // Empty global configs of the used definitions
+    let mut def_names: Vec<&str> = agents.values().map(|a| a.def_name.as_str()).collect();
+    def_names.sort();
+    def_names.dedup();
+    for def_name in def_names {
+        let Some(configs) = global_configs.get(def_name).and_then(Value::as_object) else {
+            continue;
+        };
+        for (key, value) in configs {
+            if is_empty_value(value) {
+                issue(
+                    Severity::Warning,
+                    "empty_global_config",
+                    format!("Global config {} of {} is empty", key, def_name),
+                    None,
+                    None,
+                );
+            }
+        }
+    }
+
+    issues.sort_by(|a, b| {
+        a.severity
+            .partial_cmp(&b.severity)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    issues
+}
+
+#[tauri::command]
+pub fn validate_preset_cmd(app: AppHandle, spec: PresetSpec) -> Vec<ValidationIssue> {
+    validate(&app, &spec)
+}
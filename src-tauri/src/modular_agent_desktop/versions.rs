@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context as _, Result};
+use modular_agent_core::PresetSpec;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::modular_agent_desktop::app::{
+    is_valid_preset_name, modular_agent_dir, move_path, ModularAgentApp,
+};
+use crate::modular_agent_desktop::settings::CoreSettings;
+use crate::modular_agent_desktop::workspace::data_relative_path;
+
+static MODULAR_AGENT_VERSIONS_PATH: &str = "versions";
+
+/// Number of snapshots kept per preset when `max_preset_versions` is not set.
+const DEFAULT_MAX_PRESET_VERSIONS: u32 = 20;
+
+#[derive(Clone, Serialize)]
+pub struct PresetVersion {
+    /// Snapshot identifier (milliseconds since the Unix epoch).
+    id: String,
+    timestamp: u64,
+    size: u64,
+}
+
+/// Directory holding the snapshots of a preset.
+/// e.g., "Category/MyPreset" -> ~/.modular_agent/versions/Category/MyPreset/
+fn versions_dir(preset_name: &str) -> Result<PathBuf> {
+    let dir = modular_agent_dir()?.join(MODULAR_AGENT_VERSIONS_PATH);
+    Ok(dir.join(data_relative_path(preset_name)?))
+}
+
+fn version_path(preset_name: &str, version: &str) -> Result<PathBuf> {
+    if version.is_empty() || !version.chars().all(|c| c.is_ascii_digit()) {
+        bail!("Invalid preset version: {}", version);
+    }
+    Ok(versions_dir(preset_name)?.join(format!("{}.json", version)))
+}
+
+fn max_preset_versions(app: &AppHandle) -> u32 {
+    let core_settings = app.state::<Mutex<CoreSettings>>();
+    let settings = core_settings.lock().unwrap();
+    settings
+        .max_preset_versions
+        .unwrap_or(DEFAULT_MAX_PRESET_VERSIONS)
+}
+
+/// List snapshots of a preset, newest first.
+pub(crate) fn list_versions(app: &AppHandle, preset_name: &str) -> Result<Vec<PresetVersion>> {
+    if !is_valid_preset_name(app, preset_name) {
+        bail!("Invalid preset name: {}", preset_name);
+    }
+    let dir = versions_dir(preset_name)?;
+    let mut versions = Vec::new();
+    if !dir.is_dir() {
+        return Ok(versions);
+    }
+
+    let entries =
+        std::fs::read_dir(&dir).with_context(|| format!("Failed to read directory: {:?}", dir))?;
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().unwrap_or_default() != "json" {
+            continue;
+        }
+        let Some(id) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let Ok(timestamp) = id.parse::<u64>() else {
+            continue;
+        };
+        let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+        versions.push(PresetVersion {
+            id,
+            timestamp,
+            size,
+        });
+    }
+    versions.sort_by_key(|v| std::cmp::Reverse(v.timestamp));
+
+    Ok(versions)
+}
+
+/// Read the spec stored in a snapshot.
+pub(crate) fn read_version(
+    app: &AppHandle,
+    preset_name: &str,
+    version: &str,
+) -> Result<PresetSpec> {
+    if !is_valid_preset_name(app, preset_name) {
+        bail!("Invalid preset name: {}", preset_name);
+    }
+    let path = version_path(preset_name, version)?;
+    if !path.exists() {
+        bail!("Preset version not found: {} @ {}", preset_name, version);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read preset version: {:?}", path))?;
+    PresetSpec::from_json(&content)
+        .with_context(|| format!("Failed to parse preset version: {:?}", path))
+}
+
+/// Store `json` as a new snapshot of the preset and prune old snapshots
+/// beyond the configured retention count.
+pub(crate) fn snapshot(app: &AppHandle, preset_name: &str, json: &str) -> Result<()> {
+    let max_versions = max_preset_versions(app);
+    if max_versions == 0 {
+        return Ok(());
+    }
+
+    let versions = list_versions(app, preset_name)?;
+
+    // Skip if nothing changed since the latest snapshot
+    if let Some(latest) = versions.first() {
+        let latest_path = version_path(preset_name, &latest.id)?;
+        if std::fs::read_to_string(&latest_path).is_ok_and(|s| s == json) {
+            return Ok(());
+        }
+    }
+
+    let dir = versions_dir(preset_name)?;
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir)?;
+    }
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("System time is before the Unix epoch")?
+        .as_millis();
+    let path = version_path(preset_name, &timestamp.to_string())?;
+    std::fs::write(&path, json)
+        .with_context(|| format!("Failed to write preset version: {:?}", path))?;
+
+    // `versions` does not include the new snapshot yet
+    for old in versions.iter().skip(max_versions as usize - 1) {
+        if let Ok(p) = version_path(preset_name, &old.id) {
+            if let Err(e) = std::fs::remove_file(&p) {
+                log::warn!("Failed to remove old preset version {:?}: {}", p, e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Move the snapshots of a preset (or of a whole folder) along with it.
+pub(crate) fn move_versions(old_name: &str, new_name: &str) {
+    let (Ok(old_dir), Ok(new_dir)) = (versions_dir(old_name), versions_dir(new_name)) else {
+        return;
+    };
+    if !old_dir.exists() || new_dir.exists() {
+        return;
+    }
+    if let Some(parent) = new_dir.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            log::warn!("Failed to create versions directory {:?}: {}", parent, e);
+            return;
+        }
+    }
+    if let Err(e) = std::fs::rename(&old_dir, &new_dir) {
+        log::warn!(
+            "Failed to move preset versions: {} -> {}: {}",
+            old_name,
+            new_name,
+            e
+        );
+    }
+}
+
+/// Remove all snapshots of a preset, e.g. one that was never meant to be saved.
+pub(crate) fn remove_versions(name: &str) {
+    let Ok(dir) = versions_dir(name) else {
+        return;
+    };
+    if dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            log::warn!("Failed to remove preset versions {:?}: {}", dir, e);
+        }
+    }
+}
+
+/// Move the snapshots of a deleted preset (or folder) to `dest`, e.g. into its trash entry,
+/// so a new preset with the same name starts with an empty history.
+pub(crate) fn take_versions(name: &str, dest: &Path) -> Result<()> {
+    let dir = versions_dir(name)?;
+    if !dir.exists() {
+        return Ok(());
+    }
+    move_path(&dir, dest).with_context(|| format!("Failed to move preset versions: {}", name))
+}
+
+/// Move snapshots taken by `take_versions` back to a restored preset (or folder).
+/// Existing snapshots under `name` are kept as they are.
+pub(crate) fn put_versions(src: &Path, name: &str) -> Result<()> {
+    let dir = versions_dir(name)?;
+    if !src.exists() || dir.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = dir.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    move_path(src, &dir).with_context(|| format!("Failed to restore preset versions: {}", name))
+}
+
+#[tauri::command]
+pub fn list_preset_versions_cmd(
+    app: AppHandle,
+    name: String,
+) -> Result<Vec<PresetVersion>, String> {
+    list_versions(&app, &name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_preset_version_cmd(
+    app: AppHandle,
+    name: String,
+    version: String,
+) -> Result<PresetSpec, String> {
+    read_version(&app, &name, &version).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn restore_preset_version_cmd(
+    app: AppHandle,
+    asapp: State<'_, ModularAgentApp>,
+    name: String,
+    version: String,
+) -> Result<Option<String>, String> {
+    asapp
+        .restore_preset_version(&app, &name, &version)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,197 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context as _, Result};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::modular_agent_desktop::app::{
+    parent_preset_path, ModularAgentApp, PresetListChangedPayload, EMIT_PRESET_LIST_CHANGED,
+};
+use crate::modular_agent_desktop::workspace::{find_workspace, workspace_prefix, workspace_roots};
+use crate::modular_agent_desktop::{search, tree};
+
+const EMIT_PRESET_CHANGED_ON_DISK: &str = "ma:preset_changed_on_disk";
+
+/// Delay used to coalesce bursts of filesystem events (e.g. `git pull`).
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How long a write made by the app itself is ignored by the watcher.
+const SELF_WRITE_GRACE: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Serialize)]
+struct PresetChangedOnDiskPayload {
+    name: String,
+    open: bool,
+}
+
+/// Keeps the debouncer (and its watcher thread) alive for the lifetime of the app.
+pub struct PresetsWatcher {
+    debouncer: Mutex<Debouncer<RecommendedWatcher>>,
+
+    /// Workspace roots currently being watched.
+    roots: Mutex<Vec<PathBuf>>,
+
+    /// Paths recently written by the app itself, so they are not reported as external edits.
+    self_writes: Mutex<HashMap<PathBuf, Instant>>,
+}
+
+pub fn init(app: &AppHandle) -> Result<()> {
+    let app_handle = app.clone();
+    let debouncer = new_debouncer(
+        DEBOUNCE_TIMEOUT,
+        move |res: DebounceEventResult| match res {
+            Ok(events) => {
+                let paths: Vec<PathBuf> = events.into_iter().map(|e| e.path).collect();
+                handle_changes(&app_handle, paths);
+            }
+            Err(e) => {
+                log::warn!("Presets watcher error: {}", e);
+            }
+        },
+    )
+    .context("Failed to create presets watcher")?;
+
+    app.manage(PresetsWatcher {
+        debouncer: Mutex::new(debouncer),
+        roots: Mutex::new(Vec::new()),
+        self_writes: Mutex::new(HashMap::new()),
+    });
+
+    watch_workspaces(app)
+}
+
+/// Watch the roots of all workspaces, and stop watching removed ones.
+pub(crate) fn watch_workspaces(app: &AppHandle) -> Result<()> {
+    let Some(presets_watcher) = app.try_state::<PresetsWatcher>() else {
+        return Ok(());
+    };
+    let mut debouncer = presets_watcher.debouncer.lock().unwrap();
+    let mut roots = presets_watcher.roots.lock().unwrap();
+
+    let new_roots: Vec<PathBuf> = workspace_roots(app)?.into_iter().map(|(_, r)| r).collect();
+    for root in roots.iter().filter(|r| !new_roots.contains(r)) {
+        if let Err(e) = debouncer.watcher().unwatch(root) {
+            log::warn!("Failed to unwatch {:?}: {}", root, e);
+        }
+    }
+    for root in new_roots.iter().filter(|r| !roots.contains(r)) {
+        if !root.exists() {
+            std::fs::create_dir_all(root)
+                .with_context(|| format!("Failed to create presets directory: {:?}", root))?;
+        }
+        debouncer
+            .watcher()
+            .watch(root, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch presets directory: {:?}", root))?;
+        log::info!("Watching presets directory: {:?}", root);
+    }
+    *roots = new_roots;
+
+    Ok(())
+}
+
+/// Record that the app itself is about to modify `path`.
+pub(crate) fn mark_self_write(app: &AppHandle, path: &Path) {
+    let Some(presets_watcher) = app.try_state::<PresetsWatcher>() else {
+        return;
+    };
+    let mut writes = presets_watcher.self_writes.lock().unwrap();
+    let now = Instant::now();
+    writes.retain(|_, t| now.duration_since(*t) < SELF_WRITE_GRACE);
+    writes.insert(path.to_path_buf(), now);
+}
+
+fn is_self_write(app: &AppHandle, path: &Path) -> bool {
+    let Some(presets_watcher) = app.try_state::<PresetsWatcher>() else {
+        return false;
+    };
+    let writes = presets_watcher.self_writes.lock().unwrap();
+    writes
+        .get(path)
+        .map(|t| t.elapsed() < SELF_WRITE_GRACE)
+        .unwrap_or(false)
+}
+
+fn handle_changes(app: &AppHandle, paths: Vec<PathBuf>) {
+    let mut changed_dirs = BTreeSet::new();
+    let mut changed_presets = BTreeSet::new();
+    let mut folders_changed = false;
+
+    for path in paths {
+        let Some((workspace, rel)) = find_workspace(app, &path) else {
+            continue;
+        };
+        let prefix = workspace_prefix(&workspace);
+        if rel.as_os_str().is_empty() {
+            changed_dirs.insert(prefix);
+            continue;
+        }
+        // Skip hidden files and directories (editor swap files, .git, etc.)
+        if rel
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+        {
+            continue;
+        }
+
+        // Skip temporary, backup and set-aside files written next to presets
+        if matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("tmp" | "bak" | "corrupt")
+        ) {
+            continue;
+        }
+
+        let rel_str = format!("{}{}", prefix, rel.to_string_lossy().replace('\\', "/"));
+        let parent = parent_preset_path(&rel_str);
+
+        // Metadata sidecars only change how presets are listed
+        if path.extension().unwrap_or_default() == "meta" {
+            changed_dirs.insert(parent);
+            continue;
+        }
+
+        if path.extension().unwrap_or_default() == "json" {
+            changed_dirs.insert(parent);
+            if !is_self_write(app, &path) {
+                let name = rel_str.trim_end_matches(".json").to_string();
+                changed_presets.insert(name);
+            }
+        } else if path.is_dir() || !path.exists() {
+            // A folder was created, removed or renamed
+            folders_changed = true;
+            changed_dirs.insert(parent);
+            changed_dirs.insert(rel_str);
+        }
+    }
+
+    for path in changed_dirs {
+        let _ = app.emit(EMIT_PRESET_LIST_CHANGED, PresetListChangedPayload { path });
+    }
+
+    if folders_changed {
+        search::invalidate(app);
+    }
+    if folders_changed || !changed_presets.is_empty() {
+        tree::invalidate(app);
+    }
+
+    if changed_presets.is_empty() {
+        return;
+    }
+    let asapp = app.state::<ModularAgentApp>();
+    for name in changed_presets {
+        search::refresh_preset(app, &name);
+        let open = asapp.get_preset_id(&name).is_some();
+        log::debug!("Preset changed on disk: {} (open: {})", name, open);
+        let _ = app.emit(
+            EMIT_PRESET_CHANGED_ON_DISK,
+            PresetChangedOnDiskPayload { name, open },
+        );
+    }
+}
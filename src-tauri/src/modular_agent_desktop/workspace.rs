@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{bail, Context as _, Result};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::modular_agent_desktop::app::presets_dir;
+use crate::modular_agent_desktop::settings::CoreSettings;
+use crate::modular_agent_desktop::{search, tree, watcher};
+
+/// Separates the workspace from the path in a qualified preset name.
+/// e.g., "team:Category/MyPreset". Names without it are in the default workspace.
+pub(crate) const WORKSPACE_SEPARATOR: char = ':';
+
+/// Named workspaces from the settings, kept in the app state.
+pub struct Workspaces {
+    /// Root directories by workspace name.
+    roots: Mutex<BTreeMap<String, PathBuf>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WorkspaceInfo {
+    /// Workspace name. Empty for the default workspace.
+    name: String,
+
+    /// Root directory of the workspace.
+    path: String,
+}
+
+pub(crate) fn is_valid_workspace_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && !name.starts_with('.')
+        && !name
+            .chars()
+            .any(|c| matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+}
+
+/// Load the workspaces from the settings.
+pub fn init(app: &AppHandle) -> Result<()> {
+    let configured = {
+        let core_settings = app.state::<Mutex<CoreSettings>>();
+        let settings = core_settings.lock().unwrap();
+        settings.workspaces.clone().unwrap_or_default()
+    };
+
+    let mut workspaces = BTreeMap::new();
+    for (name, dir) in configured {
+        if !is_valid_workspace_name(&name) {
+            log::warn!("Skipping workspace with an invalid name: {}", name);
+            continue;
+        }
+        let path = PathBuf::from(dir.trim());
+        if !path.is_absolute() {
+            log::warn!(
+                "Skipping workspace {}: path must be absolute: {:?}",
+                name,
+                path
+            );
+            continue;
+        }
+        if !path.exists() {
+            if let Err(e) = std::fs::create_dir_all(&path) {
+                log::warn!("Skipping workspace {}: {:?}: {}", name, path, e);
+                continue;
+            }
+        }
+        workspaces.insert(name, path);
+    }
+    log::info!("Workspaces: {:?}", workspaces);
+
+    match app.try_state::<Workspaces>() {
+        Some(state) => *state.roots.lock().unwrap() = workspaces,
+        None => {
+            app.manage(Workspaces {
+                roots: Mutex::new(workspaces),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Apply changed workspace settings: reload them, update the watcher and drop caches.
+pub(crate) fn reload(app: &AppHandle) -> Result<()> {
+    init(app)?;
+    watcher::watch_workspaces(app)?;
+    search::invalidate(app);
+    tree::invalidate(app);
+    Ok(())
+}
+
+/// Split a qualified name into its workspace and the path inside it.
+/// e.g., "team:Category/MyPreset" -> ("team", "Category/MyPreset"),
+/// "Category/MyPreset" -> ("", "Category/MyPreset")
+pub(crate) fn split_workspace(name: &str) -> (&str, &str) {
+    match name.find(WORKSPACE_SEPARATOR) {
+        Some(i) if !name[..i].contains('/') => (&name[..i], &name[i + 1..]),
+        _ => ("", name),
+    }
+}
+
+/// Prefix of the names in a workspace. e.g., "team" -> "team:", "" -> ""
+pub(crate) fn workspace_prefix(workspace: &str) -> String {
+    if workspace.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}", workspace, WORKSPACE_SEPARATOR)
+    }
+}
+
+/// Root directory of a named workspace, if it is configured.
+fn named_workspace_root(app: &AppHandle, workspace: &str) -> Option<PathBuf> {
+    let state = app.try_state::<Workspaces>()?;
+    let roots = state.roots.lock().unwrap();
+    roots.get(workspace).cloned()
+}
+
+pub(crate) fn is_known_workspace(app: &AppHandle, workspace: &str) -> bool {
+    workspace.is_empty() || named_workspace_root(app, workspace).is_some()
+}
+
+/// Root directory of a workspace. The default workspace is the presets directory.
+pub(crate) fn workspace_root(app: &AppHandle, workspace: &str) -> Result<PathBuf> {
+    if workspace.is_empty() {
+        return presets_dir();
+    }
+    named_workspace_root(app, workspace)
+        .with_context(|| format!("Unknown workspace: {}", workspace))
+}
+
+/// All workspaces and their root directories, the default workspace first.
+pub(crate) fn workspace_roots(app: &AppHandle) -> Result<Vec<(String, PathBuf)>> {
+    let mut roots = vec![(String::new(), presets_dir()?)];
+    if let Some(state) = app.try_state::<Workspaces>() {
+        let workspaces = state.roots.lock().unwrap();
+        roots.extend(workspaces.iter().map(|(n, p)| (n.clone(), p.clone())));
+    }
+    Ok(roots)
+}
+
+/// Find the workspace containing `path` and the path relative to its root.
+pub(crate) fn find_workspace(app: &AppHandle, path: &Path) -> Option<(String, PathBuf)> {
+    let roots = workspace_roots(app).ok()?;
+    roots
+        .into_iter()
+        .filter(|(_, root)| path.starts_with(root))
+        .max_by_key(|(_, root)| root.components().count())
+        .and_then(|(name, root)| Some((name, path.strip_prefix(&root).ok()?.to_path_buf())))
+}
+
+/// Path of a qualified name relative to a per-preset data directory (versions).
+/// Named workspaces are kept apart from the default one under a hidden directory.
+pub(crate) fn data_relative_path(name: &str) -> Result<PathBuf> {
+    let (workspace, path) = split_workspace(name);
+    if !workspace.is_empty() && !is_valid_workspace_name(workspace) {
+        bail!("Invalid workspace: {}", workspace);
+    }
+    let mut rel = if workspace.is_empty() {
+        PathBuf::new()
+    } else {
+        PathBuf::from(".workspaces").join(workspace)
+    };
+    for component in path.split('/') {
+        rel = rel.join(component);
+    }
+    Ok(rel)
+}
+
+#[tauri::command]
+pub fn list_workspaces_cmd(app: AppHandle) -> Result<Vec<WorkspaceInfo>, String> {
+    let roots = workspace_roots(&app).map_err(|e| e.to_string())?;
+    Ok(roots
+        .into_iter()
+        .map(|(name, path)| WorkspaceInfo {
+            name,
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect())
+}
@@ -17,28 +17,86 @@ import {
   getCoreSettings as getCoreSettingsUtils,
   setCoreSettings as setCoreSettingsUtils,
 } from "./modular_agent";
-import type { PresetFlow, PresetEdge, PresetNode, CoreSettings, PresetInfoExt } from "./types";
+import type {
+  BulkPresetResult,
+  PresetFlow,
+  PresetEdge,
+  PresetNode,
+  CoreSettings,
+  ExpectedRevision,
+  PresetConflict,
+  PresetInfoExt,
+  PresetRevision,
+} from "./types";
 
 export async function newPresetWithName(name: string): Promise<string> {
   return await invoke("new_preset_with_name_cmd", { name });
 }
 
-export async function savePreset(name: string, spec: PresetSpec): Promise<void> {
-  await invoke("save_preset_cmd", { name, spec });
+/**
+ * Save a preset and return its new revision.
+ * Rejects with a `PresetConflict` if the file changed on disk since `expected`,
+ * or since the preset was opened or last saved. An empty `expected` overwrites the file.
+ */
+export async function savePreset(
+  name: string,
+  spec: PresetSpec,
+  expected?: ExpectedRevision,
+): Promise<PresetRevision | null> {
+  return await invoke("save_preset_cmd", { name, spec, expected: expected ?? null });
+}
+
+export async function getPresetRevision(name: string): Promise<PresetRevision | null> {
+  return await invoke("get_preset_revision_cmd", { name });
+}
+
+export function isPresetConflict(e: unknown): e is PresetConflict {
+  return typeof e === "object" && e !== null && (e as PresetConflict).kind === "conflict";
 }
 
 export async function importPreset(path: string, targetDir: string): Promise<string> {
   return await invoke("import_preset_cmd", { path, targetDir });
 }
 
-export async function startPreset(id: string): Promise<void> {
-  await invoke("start_preset_cmd", { id });
+export async function importPresetFromText(
+  text: string,
+  name: string,
+  targetDir: string,
+): Promise<string> {
+  return await invoke("import_preset_from_text_cmd", { text, name, targetDir });
+}
+
+export async function startPreset(id: string, strict?: boolean): Promise<void> {
+  await invoke("start_preset_cmd", { id, strict: strict ?? null });
 }
 
 export async function stopPreset(id: string): Promise<void> {
   await invoke("stop_preset_cmd", { id });
 }
 
+/** Start presets by name and/or all presets under a folder, opening them as needed. */
+export async function startPresets(
+  selection: { names?: string[]; folder?: string },
+  strict?: boolean,
+): Promise<BulkPresetResult[]> {
+  return await invoke("start_presets_cmd", {
+    names: selection.names ?? null,
+    folder: selection.folder ?? null,
+    strict: strict ?? null,
+  });
+}
+
+/** Stop presets by name and/or all presets under a folder. */
+export async function stopPresets(selection: {
+  names?: string[];
+  folder?: string;
+}): Promise<BulkPresetResult[]> {
+  return await invoke("stop_presets_cmd", {
+    names: selection.names ?? null,
+    folder: selection.folder ?? null,
+  });
+}
+
 // Preset
 
 export function presetToFlow(info: PresetInfo, spec: PresetSpec): PresetFlow {
@@ -23,6 +23,7 @@ import {
   getCoreSettings,
   setCoreSettings,
   importPreset as importPresetAPI,
+  isPresetConflict,
   savePreset as savePresetAPI,
   newPresetWithName,
 } from "$lib/agent";
@@ -210,11 +211,21 @@ export class EditorState {
 
   // --- Preset operations ---
 
-  async savePreset() {
+  async savePreset(overwrite = false) {
     const result = await withErrorToast(async () => {
       const s = await getPresetSpec(this.preset_id);
       if (!s) return;
-      await savePresetAPI(this.name, s);
+      // The backend checks against the revision it loaded; an empty one overwrites
+      try {
+        await savePresetAPI(this.name, s, overwrite ? {} : undefined);
+      } catch (e) {
+        if (!isPresetConflict(e)) throw e;
+        toast.error(e.message, {
+          description: "Overwrite it with your changes?",
+          action: { label: "Overwrite", onClick: () => this.savePreset(true) },
+        });
+        return;
+      }
       return true;
     }, "Failed to save preset");
     if (result) this.history.markSaved();
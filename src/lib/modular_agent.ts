@@ -1,8 +1,30 @@
 import { invoke } from "@tauri-apps/api/core";
 
-import type { AgentConfigs } from "tauri-plugin-modular-agent-api";
-
-import type { CoreSettings } from "./types";
+import type { AgentConfigs, PresetSpec } from "tauri-plugin-modular-agent-api";
+
+import type {
+  BundleManifest,
+  ConflictPolicy,
+  CoreSettings,
+  DataLayout,
+  DiffSource,
+  DirEntryInfo,
+  DirtyPreset,
+  DuplicatedPreset,
+  ImportPlanEntry,
+  PresetDiff,
+  PresetMetadata,
+  PresetMetadataEdit,
+  PresetTreeNode,
+  PresetVersion,
+  SearchMatch,
+  TagCount,
+  TaggedPreset,
+  TemplateInfo,
+  TrashEntry,
+  ValidationIssue,
+  WorkspaceInfo,
+} from "./types";
 
 // Tauri
 
@@ -14,6 +36,10 @@ export async function exitApp(): Promise<void> {
   await invoke("exit_app_cmd");
 }
 
+export async function getDataLayout(): Promise<DataLayout> {
+  return await invoke("get_data_layout_cmd");
+}
+
 // settings
 
 export async function getCoreSettings(): Promise<CoreSettings> {
@@ -41,10 +67,34 @@ export async function getDirEntries(path: string): Promise<string[]> {
   return await invoke("get_dir_entries_cmd", { path });
 }
 
+export async function getDirEntryInfos(path: string): Promise<DirEntryInfo[]> {
+  return await invoke("get_dir_entry_infos_cmd", { path });
+}
+
+export async function listWorkspaces(): Promise<WorkspaceInfo[]> {
+  return await invoke("list_workspaces_cmd");
+}
+
+export async function getPresetTree(path: string, depth?: number): Promise<PresetTreeNode[]> {
+  return await invoke("get_preset_tree_cmd", { path, depth });
+}
+
 export async function openPreset(name: string): Promise<string> {
   return await invoke("open_preset_cmd", { name });
 }
 
+export async function listTemplates(): Promise<TemplateInfo[]> {
+  return await invoke("list_templates_cmd");
+}
+
+export async function newPresetFromTemplate(
+  template: string,
+  name: string,
+  params?: Record<string, unknown>,
+): Promise<string> {
+  return await invoke("new_preset_from_template_cmd", { template, name, params: params ?? null });
+}
+
 export async function closePreset(id: string): Promise<boolean> {
   return await invoke("close_preset_cmd", { id });
 }
@@ -57,6 +107,114 @@ export async function moveFolder(path: string, targetDir: string): Promise<void>
   await invoke("move_folder_cmd", { path, targetDir });
 }
 
+export async function renamePreset(name: string, newName: string): Promise<void> {
+  await invoke("rename_preset_cmd", { name, newName });
+}
+
+export async function renameFolder(path: string, newName: string): Promise<void> {
+  await invoke("rename_folder_cmd", { path, newName });
+}
+
+export async function createFolder(path: string): Promise<void> {
+  await invoke("create_folder_cmd", { path });
+}
+
+export async function deleteFolder(path: string): Promise<void> {
+  await invoke("delete_folder_cmd", { path });
+}
+
+export async function setPresetLocked(name: string, locked: boolean): Promise<void> {
+  await invoke("set_preset_locked_cmd", { name, locked });
+}
+
+export async function getPresetMetadata(name: string): Promise<PresetMetadata> {
+  return await invoke("get_preset_metadata_cmd", { name });
+}
+
+export async function setPresetMetadata(
+  name: string,
+  metadata: PresetMetadataEdit,
+): Promise<PresetMetadata> {
+  return await invoke("set_preset_metadata_cmd", { name, metadata });
+}
+
+export async function findPresetsByTags(tags: string[], path?: string): Promise<TaggedPreset[]> {
+  return await invoke("find_presets_by_tags_cmd", { tags, path: path ?? null });
+}
+
+export async function listPresetTags(path?: string): Promise<TagCount[]> {
+  return await invoke("list_preset_tags_cmd", { path: path ?? null });
+}
+
+export async function setFolderReadOnly(path: string, readOnly: boolean): Promise<void> {
+  await invoke("set_folder_read_only_cmd", { path, readOnly });
+}
+
+export async function duplicatePreset(
+  name: string,
+  targetDir: string,
+  open: boolean,
+): Promise<DuplicatedPreset> {
+  return await invoke("duplicate_preset_cmd", { name, targetDir, open });
+}
+
+export async function getDirtyPresets(): Promise<DirtyPreset[]> {
+  return await invoke("get_dirty_presets_cmd");
+}
+
 export async function deletePreset(name: string): Promise<string> {
   return await invoke("delete_preset_cmd", { name });
 }
+
+export async function listPresetVersions(name: string): Promise<PresetVersion[]> {
+  return await invoke("list_preset_versions_cmd", { name });
+}
+
+export async function getPresetVersion(name: string, version: string): Promise<PresetSpec> {
+  return await invoke("get_preset_version_cmd", { name, version });
+}
+
+export async function restorePresetVersion(name: string, version: string): Promise<string | null> {
+  return await invoke("restore_preset_version_cmd", { name, version });
+}
+
+export async function diffPresets(before: DiffSource, after: DiffSource): Promise<PresetDiff> {
+  return await invoke("diff_presets_cmd", { before, after });
+}
+
+export async function listTrash(): Promise<TrashEntry[]> {
+  return await invoke("list_trash_cmd");
+}
+
+export async function restoreTrashEntry(id: string): Promise<TrashEntry> {
+  return await invoke("restore_trash_entry_cmd", { id });
+}
+
+export async function purgeTrash(id?: string): Promise<void> {
+  await invoke("purge_trash_cmd", { id: id ?? null });
+}
+
+export async function exportPreset(name: string, dest: string): Promise<BundleManifest> {
+  return await invoke("export_preset_cmd", { name, dest });
+}
+
+export async function exportFolder(path: string, dest: string): Promise<BundleManifest> {
+  return await invoke("export_folder_cmd", { path, dest });
+}
+
+export async function importPresets(
+  path: string,
+  targetDir: string,
+  policy: ConflictPolicy,
+  dryRun: boolean,
+): Promise<ImportPlanEntry[]> {
+  return await invoke("import_presets_cmd", { path, targetDir, policy, dryRun });
+}
+
+export async function searchPresets(query: string, limit?: number): Promise<SearchMatch[]> {
+  return await invoke("search_presets_cmd", { query, limit: limit ?? null });
+}
+
+export async function validatePreset(spec: PresetSpec): Promise<ValidationIssue[]> {
+  return await invoke("validate_preset_cmd", { spec });
+}
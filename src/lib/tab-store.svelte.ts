@@ -39,6 +39,16 @@ class TabStore {
     }
   }
 
+  replaceId(id: string, newId: string) {
+    removeHistory(id);
+    delete this.runningMap[id];
+    delete this.dirtyMap[id];
+    this.tabs = this.tabs.map((t) => (t.id === id ? { ...t, id: newId } : t));
+    if (this.activeTabId === id) {
+      this.activeTabId = newId;
+    }
+  }
+
   updateName(id: string, name: string) {
     const tab = this.tabs.find((t) => t.id === id);
     if (tab) tab.name = name;
@@ -1,5 +1,5 @@
 import type { Edge, Node } from "@xyflow/svelte";
-import type { AgentSpec, PresetInfo, Viewport } from "tauri-plugin-modular-agent-api";
+import type { AgentSpec, PresetInfo, PresetSpec, Viewport } from "tauri-plugin-modular-agent-api";
 
 // Messages
 
@@ -23,6 +23,18 @@ export type AgentSpecUpdatedMessage = {
   agent_id: string;
 };
 
+export type PresetMigratedMessage = {
+  id: string;
+  name: string;
+  report: MigrationReport;
+};
+
+export type PresetDirtyChangedMessage = {
+  id: string;
+  name: string;
+  dirty: boolean;
+};
+
 // for SvelteFlow
 
 export type PresetFlow = {
@@ -54,8 +66,206 @@ export type CoreSettings = {
   show_grid?: boolean;
   grid_gap?: number;
   max_history_length?: number;
+  max_preset_versions?: number;
+  trash_retention_days?: number;
+  data_dir?: string;
+  workspaces?: Record<string, string>;
+  autosave_interval?: number;
 };
 
 export type PresetInfoExt = PresetInfo & {
   run_on_start?: boolean;
 };
+
+export type PresetRevision = {
+  hash: string;
+  mtime: number;
+};
+
+export type ExpectedRevision = {
+  hash?: string;
+  mtime?: number;
+};
+
+export type PresetConflict = {
+  kind: "conflict";
+  message: string;
+  revision: PresetRevision | null;
+  disk_spec: PresetSpec | null;
+};
+
+export type PresetVersion = {
+  id: string;
+  timestamp: number;
+  size: number;
+};
+
+export type TemplateParam = {
+  name: string;
+  label?: string;
+  description?: string;
+  default?: unknown;
+};
+
+export type TemplateInfo = {
+  name: string;
+  description: string | null;
+  params: TemplateParam[];
+};
+
+export type DirtyPreset = {
+  id: string;
+  name: string;
+};
+
+export type DiffSource =
+  | { kind: "file"; name: string }
+  | { kind: "open"; name: string }
+  | { kind: "version"; name: string; version: string };
+
+export type ValueChange = {
+  key: string;
+  before: unknown | null;
+  after: unknown | null;
+};
+
+export type PresetDiff = {
+  agents_added: { id: string; def_name: string }[];
+  agents_removed: { id: string; def_name: string }[];
+  agents_changed: {
+    id: string;
+    def_name: string;
+    configs: ValueChange[];
+    properties: ValueChange[];
+  }[];
+  connections_added: ConnectionRef[];
+  connections_removed: ConnectionRef[];
+  properties: ValueChange[];
+  layout: { agent_id: string | null; changes: ValueChange[] }[];
+  layout_only: boolean;
+  identical: boolean;
+};
+
+export type ConnectionRef = {
+  source: string;
+  source_handle: string;
+  target: string;
+  target_handle: string;
+};
+
+export type TrashEntry = {
+  id: string;
+  name: string;
+  kind: "preset" | "folder";
+  deleted_at: number;
+};
+
+export type BulkPresetResult = {
+  name: string;
+  id: string | null;
+  ok: boolean;
+  error?: string;
+};
+
+export type DuplicatedPreset = {
+  name: string;
+  id: string | null;
+};
+
+export type BundleManifest = {
+  version: number;
+  presets: string[];
+  agent_definitions: string[];
+  global_configs: Record<string, string[]>;
+};
+
+export type ConflictPolicy = "skip" | "overwrite" | "rename";
+
+export type ImportPlanEntry = {
+  source: string;
+  name: string;
+  action: "create" | "overwrite" | "rename" | "skip";
+  error?: string;
+};
+
+export type SearchMatch = {
+  name: string;
+  score: number;
+  agent_ids: string[];
+};
+
+export type DirEntryInfo = {
+  kind: "folder" | "preset";
+  name: string;
+  path: string;
+  modified: number | null;
+  size: number | null;
+  agent_count: number | null;
+  open: boolean;
+  running: boolean;
+  auto_start: boolean;
+  locked: boolean;
+  read_only: boolean;
+  metadata?: PresetMetadata;
+};
+
+export type PresetMetadata = {
+  description?: string;
+  tags?: string[];
+  icon?: string;
+  color?: string;
+  author?: string;
+  created?: number;
+  updated?: number;
+  locked?: boolean;
+};
+
+// Omitted fields are kept as they are, and `null` clears a field.
+export type PresetMetadataEdit = {
+  description?: string | null;
+  tags?: string[] | null;
+  icon?: string | null;
+  color?: string | null;
+  author?: string | null;
+};
+
+export type TaggedPreset = {
+  name: string;
+  metadata: PresetMetadata;
+};
+
+export type TagCount = {
+  tag: string;
+  count: number;
+};
+
+export type MigrationReport = {
+  from_version: number;
+  to_version: number;
+  changes: string[];
+  backup: string | null;
+};
+
+export type DataLayout = "home" | "xdg" | "portable";
+
+export type Severity = "error" | "warning" | "info";
+
+export type ValidationIssue = {
+  severity: Severity;
+  code: string;
+  message: string;
+  agent_id?: string;
+  connection?: number;
+};
+
+export type WorkspaceInfo = {
+  name: string;
+  path: string;
+};
+
+export type PresetTreeNode = {
+  kind: "folder" | "preset";
+  name: string;
+  path: string;
+  children?: PresetTreeNode[];
+};